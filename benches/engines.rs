@@ -0,0 +1,98 @@
+//! Compares the search engines on large instances.
+//!
+//! Run with `cargo bench --bench engines`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use symmetrical_palm_tree::queue::{BucketQueue, RadixHeap};
+use symmetrical_palm_tree::random::Rng;
+use symmetrical_palm_tree::{Engine, LinearBfs, LinearDijkstra, State, Step};
+
+const N: usize = 1_000_000;
+const RUNS: u32 = 5;
+
+// Run a full search with one shortcut per node, returning the average time per run.
+fn run<E: Engine<Cost = usize>>(make: impl Fn() -> E, shortcuts: &[(usize, usize)]) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        let mut solver = make();
+        while let Some(State { cost, position }) = solver.pop() {
+            let (to, weight) = shortcuts[position];
+            solver.push(cost + weight, Step::shortcut(position, to));
+        }
+        black_box(solver.distances());
+    }
+    start.elapsed() / RUNS
+}
+
+// Compare the binary heap with the other queues, and with the BFS when costs allow it.
+fn compare(name: &str, walking: &[usize], shortcuts: &[(usize, usize)]) {
+    let binary = run(
+        || LinearDijkstra::with_walking_costs_and_sources(walking.to_vec(), &[(0, 0)]),
+        shortcuts,
+    );
+    let dial = run(
+        || LinearDijkstra::with_queue(BucketQueue::new(), walking.to_vec(), &[(0, 0)]),
+        shortcuts,
+    );
+    let radix = run(
+        || LinearDijkstra::with_queue(RadixHeap::new(), walking.to_vec(), &[(0, 0)]),
+        shortcuts,
+    );
+
+    let speedup = |time: Duration| binary.as_secs_f64() / time.as_secs_f64();
+    print!(
+        "{:<24} binary {:>9.2?}  dial {:>9.2?} ({:.2}x)  radix {:>9.2?} ({:.2}x)",
+        name,
+        binary,
+        dial,
+        speedup(dial),
+        radix,
+        speedup(radix),
+    );
+
+    let zero_one = walking.iter().chain(shortcuts.iter().map(|(_, w)| w));
+    if zero_one.into_iter().all(|&cost| cost <= 1) {
+        let bfs = run(
+            || LinearBfs::with_walking_costs_and_sources(walking.to_vec(), &[(0, 0)]),
+            shortcuts,
+        );
+        print!("  bfs {:>9.2?} ({:.2}x)", bfs, speedup(bfs));
+    }
+
+    println!();
+}
+
+fn main() {
+    // A fixed seed, so that instances are the same from one run to the next.
+    let mut rng = Rng::new(0);
+    let unit = vec![1; N - 1];
+
+    let uniform = (0..N).map(|_| (rng.below(N), 1)).collect::<Vec<_>>();
+    compare("uniform shortcuts", &unit, &uniform);
+
+    let loops = (0..N).map(|i| (i, 1)).collect::<Vec<_>>();
+    compare("self-loops", &unit, &loops);
+
+    let jumps = (0..N).map(|i| ((i + N / 3) % N, 1)).collect::<Vec<_>>();
+    compare("long jumps", &unit, &jumps);
+
+    let walking = (0..N - 1).map(|_| rng.below(2)).collect::<Vec<_>>();
+    let zero_one = (0..N)
+        .map(|_| (rng.below(N), rng.below(2)))
+        .collect::<Vec<_>>();
+    compare("0-1 costs", &walking, &zero_one);
+
+    let walking = (0..N - 1).map(|_| 1 + rng.below(10)).collect::<Vec<_>>();
+    let small = (0..N)
+        .map(|_| (rng.below(N), 1 + rng.below(10)))
+        .collect::<Vec<_>>();
+    compare("costs in 1..=10", &walking, &small);
+
+    let walking = (0..N - 1).map(|_| 1 + rng.below(1000)).collect::<Vec<_>>();
+    let large = (0..N)
+        .map(|_| (rng.below(N), 1 + rng.below(1000)))
+        .collect::<Vec<_>>();
+    compare("costs in 1..=1000", &walking, &large);
+}
@@ -0,0 +1,146 @@
+//! Compares reading instances and writing distances with a straightforward line-based approach.
+//!
+//! Run with `cargo bench --bench io`.
+
+use std::env;
+use std::fs::{self, File};
+use std::hint::black_box;
+use std::io::{BufRead, BufWriter, Write};
+use std::time::{Duration, Instant};
+
+use symmetrical_palm_tree::input::{self, Format, Instance, Shortcut, Shortcuts};
+use symmetrical_palm_tree::output::{self, Writer};
+use symmetrical_palm_tree::random::Rng;
+use symmetrical_palm_tree::{Direction, LinearDijkstra, State, Step};
+
+const N: usize = 1_000_000;
+const RUNS: u32 = 5;
+
+// Return the average time per run of `f`.
+fn time(mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        f();
+    }
+    start.elapsed() / RUNS
+}
+
+// Parse a classic instance with a `String` per line, splitting and parsing each token as a string.
+fn naive_parse(input: &[u8]) -> Instance {
+    let mut lines = input.lines();
+    let n = lines.next().unwrap().unwrap().parse::<usize>().unwrap();
+    let shortcuts = lines
+        .next()
+        .unwrap()
+        .unwrap()
+        .split(' ')
+        .map(|a| {
+            let to = a.parse::<usize>().unwrap() - 1;
+            Shortcut { to, cost: 1 }
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(shortcuts.len(), n);
+
+    Instance {
+        walking: vec![1; n - 1],
+        directions: vec![Direction::Both; n - 1],
+        ring: false,
+        shortcuts: Shortcuts::one_per_node(shortcuts),
+    }
+}
+
+// Parse an edge list instance with a `String` per line.
+fn naive_edges(input: &[u8]) -> Instance {
+    let mut lines = input.lines().map(Result::unwrap);
+    let header = lines.next().unwrap();
+    let (n, m) = header.split_once(' ').unwrap();
+    let (n, m) = (n.parse::<usize>().unwrap(), m.parse::<usize>().unwrap());
+    let edges = lines
+        .take(m)
+        .map(|line| {
+            let (u, v) = line.split_once(' ').unwrap();
+            let to = v.parse::<usize>().unwrap() - 1;
+            (u.parse::<usize>().unwrap() - 1, Shortcut { to, cost: 1 })
+        })
+        .collect::<Vec<_>>();
+
+    Instance {
+        walking: vec![1; n - 1],
+        directions: vec![Direction::Both; n - 1],
+        ring: false,
+        shortcuts: Shortcuts::from_edges(n, &edges),
+    }
+}
+
+// Print the average times of the straightforward approach and of ours.
+fn compare(name: &str, naive: Duration, ours: Duration) {
+    println!(
+        "{:<16} naive {:>9.2?}  ours {:>9.2?} ({:.2}x)",
+        name,
+        naive,
+        ours,
+        naive.as_secs_f64() / ours.as_secs_f64()
+    );
+}
+
+// Write the distances with one unbuffered write per node.
+fn naive_write(mut out: &File, distances: &[Option<usize>]) {
+    let text = |distance: &Option<usize>| distance.map_or("-1".to_string(), |d| d.to_string());
+    let (first, rest) = distances.split_first().unwrap();
+    write!(out, "{}", text(first)).unwrap();
+    for n in rest {
+        write!(out, " {}", text(n)).unwrap();
+    }
+    writeln!(out).unwrap();
+}
+
+fn main() {
+    // A fixed seed, so that instances are the same from one run to the next.
+    let mut rng = Rng::new(0);
+    let mut input = format!("{}\n", N).into_bytes();
+    for i in 0..N {
+        let sep = if i + 1 < N { " " } else { "\n" };
+        write!(input, "{}{}", 1 + rng.below(N), sep).unwrap();
+    }
+
+    let naive = time(|| {
+        black_box(naive_parse(&input));
+    });
+    let reader = time(|| {
+        black_box(input::parse(&input[..], Format::Classic).unwrap());
+    });
+    compare("classic input", naive, reader);
+
+    let mut edges = format!("{} {}\n", N, N).into_bytes();
+    for i in 0..N {
+        writeln!(edges, "{} {}", i + 1, 1 + rng.below(N)).unwrap();
+    }
+
+    let naive = time(|| {
+        black_box(naive_edges(&edges));
+    });
+    let reader = time(|| {
+        black_box(input::parse(&edges[..], Format::Edges).unwrap());
+    });
+    compare("edge list input", naive, reader);
+
+    let instance = input::parse(&input[..], Format::Classic).unwrap();
+    let mut solver = LinearDijkstra::new(N);
+    while let Some(State { cost, position }) = solver.pop() {
+        let to = instance.shortcuts[position][0].to;
+        solver.push(cost + 1, Step::shortcut(position, to));
+    }
+
+    let path = env::temp_dir().join("symmetrical-palm-tree-io-bench.txt");
+    let file = File::create(&path).unwrap();
+    let naive = time(|| naive_write(&file, solver.distances()));
+    let writer = time(|| {
+        let mut writer = Writer::new(BufWriter::new(&file), output::Format::Plain);
+        writer.write(&solver).unwrap();
+        writer.into_inner().unwrap();
+    });
+    compare("output", naive, writer);
+
+    drop(file);
+    fs::remove_file(path).unwrap();
+}
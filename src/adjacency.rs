@@ -0,0 +1,123 @@
+//! Explicit adjacency lists, for graphs that have no implicit structure.
+//!
+//! With an [`Adjacency`] topology, a [`Dijkstra`](crate::Dijkstra) solver walks along every edge
+//! of the graph on its own, so that there is nothing left to push in between calls to `pop`:
+//!
+//! ```
+//! use symmetrical_palm_tree::adjacency::Adjacency;
+//! use symmetrical_palm_tree::Dijkstra;
+//!
+//! let graph: Adjacency = Adjacency::from_edges(4, &[(0, 1, 5), (0, 2, 1), (2, 1, 1), (1, 3, 1)]);
+//! let mut solver = Dijkstra::with_topology(graph, &[(0, 0)]);
+//! while solver.pop().is_some() {}
+//!
+//! assert_eq!(solver.distances(), &[Some(0), Some(2), Some(1), Some(3)]);
+//! ```
+
+use crate::cost::Cost;
+use crate::{Step, StepKind, Topology};
+
+/// A directed graph given by the edges leaving each node, each with its own cost.
+///
+/// The edges are stored in a single array, sorted by the node they leave, which avoids an
+/// allocation per node.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Adjacency<C = usize> {
+    // The edges leaving node i are edges[offsets[i]..offsets[i + 1]], as (to, cost) pairs.
+    offsets: Vec<usize>,
+    edges: Vec<(usize, C)>,
+}
+
+// Sort the `(node, item)` pairs of `items` by node with a counting sort, for a graph with `n`
+// nodes. Returns the offsets and items of a compressed adjacency list: the items of node i are
+// items[offsets[i]..offsets[i + 1]], in the order in which they appear in `items`.
+pub(crate) fn group<T: Copy>(
+    n: usize,
+    items: impl Iterator<Item = (usize, T)> + Clone,
+) -> (Vec<usize>, Vec<T>) {
+    let mut offsets = vec![0; n + 1];
+    for (node, _) in items.clone() {
+        offsets[node + 1] += 1;
+    }
+    for i in 0..n {
+        offsets[i + 1] += offsets[i];
+    }
+
+    // Every slot is overwritten below, so the first item is only there to fill them until then.
+    let Some((_, first)) = items.clone().next() else {
+        return (offsets, Vec::new());
+    };
+    let mut next = offsets.clone();
+    let mut grouped = vec![first; offsets[n]];
+    for (node, item) in items {
+        grouped[next[node]] = item;
+        next[node] += 1;
+    }
+
+    (offsets, grouped)
+}
+
+impl<C> Default for Adjacency<C> {
+    fn default() -> Self {
+        Adjacency {
+            offsets: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+}
+
+impl<C: Cost> Adjacency<C> {
+    /// A graph with `n` nodes and the `(from, to, cost)` edges in `edges`.
+    ///
+    /// The edges leaving each node are kept in the order in which they appear in `edges`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any edge leaves or leads to a node that is not in the graph.
+    pub fn from_edges(n: usize, edges: &[(usize, usize, C)]) -> Self {
+        for &(from, to, _) in edges {
+            assert!(from < n && to < n, "edge is outside of the graph");
+        }
+
+        let (offsets, edges) = group(n, edges.iter().map(|&(from, to, cost)| (from, (to, cost))));
+        Adjacency { offsets, edges }
+    }
+
+    /// The number of nodes.
+    pub fn len(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    /// Whether the graph has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The `(to, cost)` edges leaving `node`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` is not in the graph.
+    pub fn edges(&self, node: usize) -> &[(usize, C)] {
+        &self.edges[self.offsets[node]..self.offsets[node + 1]]
+    }
+}
+
+impl<C: Cost> Topology for Adjacency<C> {
+    type Cost = C;
+
+    fn len(&self) -> usize {
+        Adjacency::len(self)
+    }
+
+    fn neighbours(&self, position: usize) -> impl Iterator<Item = (Step, C)> {
+        self.edges(position).iter().map(move |&(to, cost)| {
+            let step = Step {
+                from: position,
+                to,
+                kind: StepKind::Walk,
+            };
+            (step, cost)
+        })
+    }
+}
@@ -0,0 +1,273 @@
+// Command-line parsing.
+
+use std::path::PathBuf;
+use std::process;
+
+use symmetrical_palm_tree::generate::Distribution;
+use symmetrical_palm_tree::input::Format;
+use symmetrical_palm_tree::{output, PATH_COUNT_MODULUS};
+
+const USAGE: &str = "\
+usage: symmetrical-palm-tree [command] [options]
+
+commands:
+    solve                            print the distances to every node (default)
+    query                            answer the `s t` queries following each instance
+    path <target>                    print the route to <target>
+    bench                            time each search engine on the input
+    generate                         print a random instance in the classic format
+    validate <answer>                check that the file <answer> holds the distances to every node,
+                                     or print the route to the first node where it does not
+
+options:
+    --input <file>                   read the input from <file> instead of stdin
+    --format classic|weighted|edges  input format (default: classic)
+    --start <node>                   start the search from <node> (default: 1)
+    --source <node>[:<cost>]         add <node> as a source with initial cost <cost> (default: 0)
+    --cases                          the input starts with a number of instances to solve
+    --ring                           the last node of each instance is adjacent to the first one
+    --queue binary|dial|radix        priority queue for Dijkstra's algorithm (default: a BFS when
+                                     all costs are 0 or 1, and a binary heap otherwise)
+    --output plain|json|csv          format of the distances (default: plain)
+    --predecessors                   include the predecessor of each node in json or csv output
+    --paths                          include the number of shortest routes to each node in json or
+                                     csv output
+    --unreachable <marker>           distance printed for unreachable nodes in plain or csv output
+                                     (default: -1)
+    --reachable-only                 leave out the nodes that cannot be reached
+    --summary                        print the number of unreachable nodes to stderr
+    --modulus <m>                    count routes modulo <m> (default: 1000000007)
+    --path <target>                  same as the path command, kept for compatibility
+    --queries                        same as the query command, kept for compatibility
+    --runs <count>                   number of runs to average over in bench (default: 5)
+    --seed <seed>                    seed of the generated instance (default: 0)
+    --size <n>                       number of nodes of the generated instance (default: 1000)
+    --distribution <shortcuts>       shortcuts of the generated instance (default: uniform):
+                                     uniform        to random nodes
+                                     self-loops     from each node to itself
+                                     chains         far ahead, so that routes chain jumps
+                                     churn          from i to 2i, to keep the queue full";
+
+pub fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn help() -> ! {
+    println!("{}", USAGE);
+    process::exit(0);
+}
+
+// The subcommands. Nodes are 1-indexed, like the input.
+pub enum Command {
+    Solve,
+    Query,
+    Path {
+        target: usize,
+    },
+    Validate {
+        answer: PathBuf,
+    },
+    Bench {
+        runs: u32,
+    },
+    Generate {
+        seed: u64,
+        size: usize,
+        distribution: Distribution,
+    },
+}
+
+// The priority queues that can be selected for Dijkstra's algorithm.
+pub enum Queue {
+    Binary,
+    Dial,
+    Radix,
+}
+
+// The parsed command line.
+pub struct Args {
+    pub command: Command,
+    pub input: Option<PathBuf>,
+    pub format: Format,
+    // The option each source comes from, for error messages, with its node and initial cost.
+    pub sources: Vec<(&'static str, usize, usize)>,
+    pub cases: bool,
+    pub ring: bool,
+    pub queue: Option<Queue>,
+    pub output: output::Format,
+    pub predecessors: bool,
+    pub paths: bool,
+    pub unreachable: Option<String>,
+    pub reachable_only: bool,
+    pub summary: bool,
+    pub modulus: usize,
+}
+
+// Parse a `node[:cost]` source specification.
+fn source(spec: &str) -> Option<(usize, usize)> {
+    match spec.split_once(':') {
+        Some((node, cost)) => Some((node.parse().ok()?, cost.parse().ok()?)),
+        None => Some((spec.parse().ok()?, 0)),
+    }
+}
+
+impl Args {
+    // Parse the arguments following the program name, exiting with the usage on errors.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Args {
+        let mut args = args.into_iter().peekable();
+
+        // Without a command, we solve the instance.
+        let command = match args.next_if(|arg| !arg.starts_with('-')).as_deref() {
+            None | Some("solve") => Command::Solve,
+            Some("query") => Command::Query,
+            Some("path") => match args.next().map(|t| t.parse()) {
+                Some(Ok(target)) => Command::Path { target },
+                _ => usage(),
+            },
+            Some("validate") => match args.next() {
+                Some(answer) => Command::Validate {
+                    answer: answer.into(),
+                },
+                None => usage(),
+            },
+            Some("bench") => Command::Bench { runs: 5 },
+            Some("generate") => Command::Generate {
+                seed: 0,
+                size: 1000,
+                distribution: Distribution::Uniform,
+            },
+            Some("help") => help(),
+            Some(_) => usage(),
+        };
+
+        let mut parsed = Args {
+            command,
+            input: None,
+            format: Format::Classic,
+            sources: Vec::new(),
+            cases: false,
+            ring: false,
+            queue: None,
+            output: output::Format::Plain,
+            predecessors: false,
+            paths: false,
+            unreachable: None,
+            reachable_only: false,
+            summary: false,
+            modulus: PATH_COUNT_MODULUS,
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input" => match args.next() {
+                    Some(path) => parsed.input = Some(path.into()),
+                    None => usage(),
+                },
+                "--start" => match args.next().map(|s| s.parse::<usize>()) {
+                    Some(Ok(s)) => parsed.sources.push(("--start", s, 0)),
+                    _ => usage(),
+                },
+                "--source" => match args.next().as_deref().and_then(source) {
+                    Some((s, cost)) => parsed.sources.push(("--source", s, cost)),
+                    None => usage(),
+                },
+                "--format" => match args.next().as_deref() {
+                    Some("classic") => parsed.format = Format::Classic,
+                    Some("weighted") => parsed.format = Format::Weighted,
+                    Some("edges") => parsed.format = Format::Edges,
+                    _ => usage(),
+                },
+                "--cases" => parsed.cases = true,
+                "--ring" => parsed.ring = true,
+                "--queue" => match args.next().as_deref() {
+                    Some("binary") => parsed.queue = Some(Queue::Binary),
+                    Some("dial") => parsed.queue = Some(Queue::Dial),
+                    Some("radix") => parsed.queue = Some(Queue::Radix),
+                    _ => usage(),
+                },
+                "--output" => match args.next().as_deref() {
+                    Some("plain") => parsed.output = output::Format::Plain,
+                    Some("json") => parsed.output = output::Format::Json,
+                    Some("csv") => parsed.output = output::Format::Csv,
+                    _ => usage(),
+                },
+                "--predecessors" => parsed.predecessors = true,
+                "--paths" => parsed.paths = true,
+                "--unreachable" => match args.next() {
+                    Some(marker) => parsed.unreachable = Some(marker),
+                    None => usage(),
+                },
+                "--reachable-only" => parsed.reachable_only = true,
+                "--summary" => parsed.summary = true,
+                "--modulus" => match args.next().map(|m| m.parse()) {
+                    Some(Ok(m)) if m > 0 => parsed.modulus = m,
+                    _ => usage(),
+                },
+                // These flags predate the subcommands, and can only replace solving.
+                "--path" => match (&parsed.command, args.next().map(|t| t.parse())) {
+                    (Command::Solve, Some(Ok(target))) => parsed.command = Command::Path { target },
+                    _ => usage(),
+                },
+                "--queries" => match parsed.command {
+                    Command::Solve => parsed.command = Command::Query,
+                    _ => usage(),
+                },
+                "--runs" => match (&mut parsed.command, args.next().map(|r| r.parse())) {
+                    (Command::Bench { runs }, Some(Ok(r))) if r > 0 => *runs = r,
+                    _ => usage(),
+                },
+                "--seed" => match (&mut parsed.command, args.next().map(|s| s.parse())) {
+                    (Command::Generate { seed, .. }, Some(Ok(s))) => *seed = s,
+                    _ => usage(),
+                },
+                "--size" => match (&mut parsed.command, args.next().map(|n| n.parse())) {
+                    (Command::Generate { size, .. }, Some(Ok(n))) if n > 0 => *size = n,
+                    _ => usage(),
+                },
+                "--distribution" => match (&mut parsed.command, args.next().as_deref()) {
+                    (Command::Generate { distribution, .. }, Some(d)) => {
+                        *distribution = match d {
+                            "uniform" => Distribution::Uniform,
+                            "self-loops" => Distribution::SelfLoops,
+                            "chains" => Distribution::Chains,
+                            "churn" => Distribution::Churn,
+                            _ => usage(),
+                        }
+                    }
+                    _ => usage(),
+                },
+                "--help" | "-h" => help(),
+                _ => usage(),
+            }
+        }
+
+        // Queries come with their own sources, only the distances have a choice of output format
+        // and of what to do with unreachable nodes, plain output has no extra columns, JSON has no
+        // unreachable marker, bench tries every queue, and generate has no input.
+        let distances = matches!(parsed.command, Command::Solve);
+        let unreachable = parsed.unreachable.is_some() || parsed.reachable_only || parsed.summary;
+        let invalid = match parsed.command {
+            Command::Query => !parsed.sources.is_empty(),
+            Command::Bench { .. } => parsed.queue.is_some(),
+            Command::Generate { .. } => {
+                parsed.input.is_some()
+                    || parsed.format != Format::Classic
+                    || !parsed.sources.is_empty()
+                    || parsed.cases
+                    || parsed.ring
+                    || parsed.queue.is_some()
+            }
+            Command::Solve | Command::Path { .. } | Command::Validate { .. } => false,
+        };
+        if invalid
+            || ((parsed.output != output::Format::Plain || unreachable) && !distances)
+            || (parsed.unreachable.is_some() && parsed.output == output::Format::Json)
+            || ((parsed.predecessors || parsed.paths) && parsed.output == output::Format::Plain)
+        {
+            usage();
+        }
+
+        parsed
+    }
+}
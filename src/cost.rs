@@ -0,0 +1,127 @@
+//! The types that can be used as costs.
+//!
+//! Solvers are generic over their [`Cost`] type, which defaults to `usize`. Any unsigned integer
+//! type can be used, as well as [`Float`] for floating-point costs. Only unsigned integers, which
+//! implement [`Unsigned`], can be used with the integer [`queue`](crate::queue)s.
+//!
+//! Adding up costs never wraps around: the solvers use [`Cost::checked_add`], and panic when the
+//! distance to a node might not be representable rather than silently computing a wrong one.
+//! Overflowing paths to nodes that have already been reached are simply dropped, as they cannot be
+//! shorter.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A non-negative cost, which can be added up along paths and compared.
+pub trait Cost: Copy + Ord + fmt::Debug + fmt::Display {
+    /// The cost of an empty path.
+    const ZERO: Self;
+
+    /// The unit cost, used by default for walking.
+    const ONE: Self;
+
+    /// `self + other`, or `None` if the sum cannot be represented.
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// `self + other`, or the largest representable cost if the sum is larger.
+    fn saturating_add(self, other: Self) -> Self;
+}
+
+/// Costs that are unsigned integers, which the integer [`queue`](crate::queue)s rely on.
+pub trait Unsigned: Cost {
+    /// The number of bits of the type.
+    const BITS: u32;
+
+    /// One more than the index of the highest bit in which `self` and `other` differ, or 0 if
+    /// they are equal.
+    fn highest_differing_bit(self, other: Self) -> u32;
+
+    /// `self - base`, as an index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `base` is larger than `self`, or if the difference does not fit in a `usize`.
+    fn offset_from(self, base: Self) -> usize;
+}
+
+macro_rules! unsigned {
+    ($($t:ty)*) => {$(
+        impl Cost for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn saturating_add(self, other: Self) -> Self {
+                <$t>::saturating_add(self, other)
+            }
+        }
+
+        impl Unsigned for $t {
+            const BITS: u32 = <$t>::BITS;
+
+            fn highest_differing_bit(self, other: Self) -> u32 {
+                <$t>::BITS - (self ^ other).leading_zeros()
+            }
+
+            fn offset_from(self, base: Self) -> usize {
+                usize::try_from(self - base).expect("cost offset does not fit in a usize")
+            }
+        }
+    )*};
+}
+
+unsigned!(u8 u16 u32 u64 u128 usize);
+
+/// A floating-point cost, which is finite and non-negative so that costs are totally ordered.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Float(f64);
+
+impl Float {
+    /// The cost `value`, or `None` if it is negative, infinite or NaN.
+    pub fn new(value: f64) -> Option<Self> {
+        // Adding 0 turns -0 into 0, which compare differently with `total_cmp`.
+        (value.is_finite() && value >= 0.0).then_some(Float(value + 0.0))
+    }
+
+    /// The value of the cost.
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+impl Eq for Float {}
+
+impl Ord for Float {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl PartialOrd for Float {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Float {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Cost for Float {
+    const ZERO: Self = Float(0.0);
+    const ONE: Self = Float(1.0);
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        let sum = self.0 + other.0;
+        sum.is_finite().then_some(Float(sum))
+    }
+
+    fn saturating_add(self, other: Self) -> Self {
+        self.checked_add(other).unwrap_or(Float(f64::MAX))
+    }
+}
@@ -0,0 +1,46 @@
+//! Random instances in the classic format, for testing and benchmarking at scale.
+//!
+//! The shortcuts are drawn according to a [`Distribution`] with a [`Rng`], so that the same seed
+//! always gives the same instance.
+
+use crate::random::Rng;
+
+/// The shapes of shortcut arrays that can be generated.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Distribution {
+    /// Each shortcut leads to a node drawn uniformly at random. This is the default.
+    #[default]
+    Uniform,
+    /// Each node has a shortcut to itself (`a_i = i`), so no shortcut is ever useful and the
+    /// distances are those of walking alone, which are the largest possible ones.
+    SelfLoops,
+    /// Each shortcut jumps far ahead, between an eighth and a quarter of the line, so that the
+    /// routes to far away nodes chain several jumps.
+    Chains,
+    /// Node `i` has a shortcut to node `2i + 1`, wrapping around the line, so that the number of
+    /// nodes at each distance roughly doubles until the line is covered. This keeps a wide
+    /// frontier of states scattered along the line in the queue.
+    Churn,
+}
+
+/// The 0-indexed shortcuts of a classic instance with `n` nodes, where `shortcuts[i]` is the
+/// destination of the shortcut leaving node `i`.
+///
+/// # Panics
+///
+/// Panics if `n` is 0.
+pub fn shortcuts(n: usize, distribution: Distribution, rng: &mut Rng) -> Vec<usize> {
+    assert!(n > 0, "an instance needs at least one node");
+
+    match distribution {
+        Distribution::Uniform => (0..n).map(|_| rng.below(n)).collect(),
+        Distribution::SelfLoops => (0..n).collect(),
+        Distribution::Chains => {
+            let (shortest, longest) = ((n / 8).max(2), (n / 4).max(2));
+            (0..n)
+                .map(|i| (i + rng.between(shortest, longest)).min(n - 1))
+                .collect()
+        }
+        Distribution::Churn => (0..n).map(|i| (2 * i + 1) % n).collect(),
+    }
+}
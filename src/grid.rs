@@ -0,0 +1,198 @@
+//! Two-dimensional grids for [`GridDijkstra`](crate::GridDijkstra).
+//!
+//! The cells of a grid are the nodes of the graph, numbered row by row, and walking between
+//! adjacent cells is implicit, like walking along a line. Shortcuts are pushed by the user in the
+//! same way:
+//!
+//! ```
+//! use symmetrical_palm_tree::grid::{Connectivity, Grid};
+//! use symmetrical_palm_tree::{GridDijkstra, State, Step};
+//!
+//! // A 4 × 3 grid with a wall in its third column, except at the bottom.
+//! let grid = Grid::new(4, 3).with_obstacles(&[(2, 0), (2, 1)]);
+//! let start = grid.node(0, 0);
+//! let teleporter = (grid.node(1, 0), grid.node(3, 0));
+//!
+//! let mut solver = GridDijkstra::with_topology(grid, &[(start, 0)]);
+//! while let Some(State { cost, position }) = solver.pop() {
+//!     if position == teleporter.0 {
+//!         solver.push(cost + 1, Step::shortcut(position, teleporter.1));
+//!     }
+//! }
+//!
+//! let grid = solver.topology();
+//! assert_eq!(solver.distances()[grid.node(3, 0)], Some(2));
+//! assert_eq!(solver.distances()[grid.node(2, 2)], Some(4));
+//!
+//! // Without obstacles, an 8-connected grid is walked like a king moves on a chessboard.
+//! let grid = Grid::new(4, 3).with_connectivity(Connectivity::Eight);
+//! let mut solver = GridDijkstra::with_topology(grid, &[(start, 0)]);
+//! while solver.pop().is_some() {}
+//! assert_eq!(solver.distances()[11], Some(3));
+//! ```
+
+use crate::cost::Cost;
+use crate::{Step, StepKind, Topology};
+
+/// The cells that each cell of a grid is adjacent to.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Connectivity {
+    /// The four cells sharing a side with it. This is the default.
+    #[default]
+    Four,
+    /// The eight cells sharing a side or a corner with it.
+    Eight,
+}
+
+// The offsets of the cells sharing a side with a cell, then of those only sharing a corner.
+const SIDES: [(isize, isize); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const CORNERS: [(isize, isize); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+/// A grid of `width × height` cells, some of which can be obstacles.
+///
+/// The cell in column `x` and row `y` is node `y * width + x`. Walking between adjacent cells
+/// costs 1 by default, both through their sides and, for 8-connected grids, through their
+/// corners. Obstacles can neither be walked to nor from, but shortcuts are up to the user and can
+/// still lead to or leave them. Diagonal moves only depend on the cell they lead to, so they can
+/// squeeze between two obstacles that touch by a corner.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grid<C = usize> {
+    width: usize,
+    height: usize,
+    connectivity: Connectivity,
+    // Whether each cell is an obstacle, indexed by node.
+    obstacles: Vec<bool>,
+    // The cost of walking to a cell sharing a side, or only a corner, with the current one.
+    side: C,
+    corner: C,
+}
+
+impl<C: Cost> Grid<C> {
+    /// A 4-connected grid of `width × height` cells without any obstacle, where all moves cost 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grid has no cell.
+    pub fn new(width: usize, height: usize) -> Self {
+        assert!(width > 0 && height > 0, "a grid needs at least one cell");
+
+        Grid {
+            width,
+            height,
+            connectivity: Connectivity::Four,
+            obstacles: vec![false; width * height],
+            side: C::ONE,
+            corner: C::ONE,
+        }
+    }
+
+    /// Connect each cell to the cells around it according to `connectivity`.
+    pub fn with_connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
+        self
+    }
+
+    /// Make walking through the side of a cell cost `side`, and walking through its corner cost
+    /// `corner`. The latter only matters for 8-connected grids.
+    pub fn with_costs(mut self, side: C, corner: C) -> Self {
+        self.side = side;
+        self.corner = corner;
+        self
+    }
+
+    /// Turn the cells at the given `(x, y)` coordinates into obstacles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the coordinates is outside of the grid.
+    pub fn with_obstacles(mut self, obstacles: &[(usize, usize)]) -> Self {
+        for &(x, y) in obstacles {
+            let node = self.node(x, y);
+            self.obstacles[node] = true;
+        }
+
+        self
+    }
+
+    /// The number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The number of cells.
+    pub fn len(&self) -> usize {
+        self.obstacles.len()
+    }
+
+    /// Whether there are no cells, which is never the case.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// The node of the cell in column `x` and row `y`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is outside of the grid.
+    pub fn node(&self, x: usize, y: usize) -> usize {
+        assert!(
+            x < self.width && y < self.height,
+            "cell is outside of the grid"
+        );
+
+        y * self.width + x
+    }
+
+    /// The `(x, y)` coordinates of the cell of `node`.
+    pub fn coordinates(&self, node: usize) -> (usize, usize) {
+        (node % self.width, node / self.width)
+    }
+
+    /// Whether the cell of `node` is an obstacle.
+    pub fn is_obstacle(&self, node: usize) -> bool {
+        self.obstacles[node]
+    }
+}
+
+impl<C: Cost> Topology for Grid<C> {
+    type Cost = C;
+
+    fn len(&self) -> usize {
+        Grid::len(self)
+    }
+
+    fn neighbours(&self, position: usize) -> impl Iterator<Item = (Step, C)> {
+        let (x, y) = self.coordinates(position);
+        let corners = match self.connectivity {
+            Connectivity::Four => &[][..],
+            Connectivity::Eight => &CORNERS[..],
+        };
+
+        // Obstacles have no neighbours at all.
+        let offsets = SIDES
+            .iter()
+            .map(|&offset| (offset, self.side))
+            .chain(corners.iter().map(|&offset| (offset, self.corner)))
+            .filter(move |_| !self.obstacles[position]);
+
+        offsets.filter_map(move |((dx, dy), cost)| {
+            let (x, y) = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+            if x >= self.width || y >= self.height {
+                return None;
+            }
+
+            let to = y * self.width + x;
+            let step = Step {
+                from: position,
+                to,
+                kind: StepKind::Walk,
+            };
+            (!self.obstacles[to]).then_some((step, cost))
+        })
+    }
+}
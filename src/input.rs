@@ -0,0 +1,863 @@
+//! Parsing of problem instances.
+//!
+//! Instances come in one of the following [`Format`]s. In all cases, nodes are 1-indexed.
+//!
+//! - The classic format is made of two lines: the number of nodes `n`, followed by the `n`
+//!   space-separated shortcuts `a_1 ... a_n`, where `a_i` is the destination of the shortcut
+//!   leaving node `i`. Walking between adjacent nodes and taking a shortcut both cost 1.
+//!
+//! - The weighted format is made of four lines: the number of nodes `n`, the `n - 1` walking costs
+//!   `c_1 ... c_{n-1}` where `c_i` is the cost of walking between `i` and `i + 1`, the `n`
+//!   shortcuts `a_1 ... a_n` as in the classic format, and the `n` shortcut costs `w_1 ... w_n`
+//!   where `w_i` is the cost of taking the shortcut leaving node `i`.
+//!
+//! - The edge list format starts with a line containing the number of nodes `n` and the number of
+//!   shortcuts `m`, followed by `m` lines `u v [w]` describing a shortcut from `u` to `v` with cost
+//!   `w`, or 1 if omitted. A node can have any number of shortcuts, including none. Walking between
+//!   adjacent nodes costs 1.
+//!
+//! In all formats, the shortcuts can be preceded by the `n - 1` directions `d_1 ... d_{n-1}` in
+//! which each segment between `i` and `i + 1` can be walked: `=` for both ways, `>` for forward
+//! only (from `i` to `i + 1`), `<` for backward only, and `x` if it is closed. This line is
+//! optional, and all segments are two-way without it.
+//!
+//! The nodes can also form a ring, where node `n` is adjacent to node 1, if the [`Reader`] is told
+//! so with [`Reader::rings`]. There is then an `n`-th segment between `n` and 1, which comes last
+//! in the walking costs and the directions: both have `n` values rather than `n - 1`.
+//!
+//! Values can be separated by any amount of whitespace, including line breaks, so the layouts above
+//! are only conventions: for instance, long shortcut lines can be wrapped. The exception is the
+//! optional cost `w` of the edge list format, which must be on the same line as `v`.
+//!
+//! Several instances in the same format can be bundled in a single input, preceded by their
+//! number `t`. They are read one after the other with a [`Reader`].
+//!
+//! A [`Reader`] can also read candidate answers, in the plain output format: the distance to each
+//! node, or `-1` for the nodes that cannot be reached.
+
+use std::error;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::ops;
+
+use crate::{adjacency, Direction, Line};
+
+/// The supported input formats. See the [module documentation](self) for details.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Format {
+    /// Unit costs everywhere, one shortcut per node.
+    #[default]
+    Classic,
+    /// Explicit walking and shortcut costs.
+    Weighted,
+    /// Any number of shortcuts per node, given as a list of edges.
+    Edges,
+}
+
+/// A shortcut leaving a node.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Shortcut {
+    /// The 0-indexed destination of the shortcut.
+    pub to: usize,
+    /// The cost of taking the shortcut.
+    pub cost: usize,
+}
+
+/// The shortcuts leaving each node, indexed by node.
+///
+/// The shortcuts of all nodes are stored in a single vector rather than in a vector per node, which
+/// matters for instances with millions of nodes.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Shortcuts {
+    // The shortcuts leaving node i are shortcuts[offsets[i]..offsets[i + 1]].
+    offsets: Vec<usize>,
+    shortcuts: Vec<Shortcut>,
+}
+
+impl Shortcuts {
+    /// The shortcuts of an instance where each node `i` has the single shortcut `shortcuts[i]`.
+    pub fn one_per_node(shortcuts: Vec<Shortcut>) -> Self {
+        Shortcuts {
+            offsets: (0..=shortcuts.len()).collect(),
+            shortcuts,
+        }
+    }
+
+    /// The shortcuts of an instance with `n` nodes, from a list of `(from, shortcut)` edges. The
+    /// shortcuts leaving each node keep the order in which they appear in `edges`.
+    pub fn from_edges(n: usize, edges: &[(usize, Shortcut)]) -> Self {
+        let (offsets, shortcuts) = adjacency::group(n, edges.iter().copied());
+        Shortcuts { offsets, shortcuts }
+    }
+
+    /// The number of nodes.
+    pub fn len(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    /// Whether there are no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over the shortcuts leaving each node, in order.
+    pub fn iter(&self) -> impl Iterator<Item = &[Shortcut]> + '_ {
+        self.offsets
+            .windows(2)
+            .map(|range| &self.shortcuts[range[0]..range[1]])
+    }
+}
+
+impl ops::Index<usize> for Shortcuts {
+    type Output = [Shortcut];
+
+    /// The shortcuts leaving `node`.
+    fn index(&self, node: usize) -> &[Shortcut] {
+        &self.shortcuts[self.offsets[node]..self.offsets[node + 1]]
+    }
+}
+
+/// A parsed instance, with nodes converted to 0-indexed nodes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Instance {
+    /// The cost of walking between each node `i` and `i + 1`, and between the last node and 0 for
+    /// a ring.
+    pub walking: Vec<usize>,
+    /// The directions in which each segment can be walked, with the same indices as `walking`.
+    pub directions: Vec<Direction>,
+    /// Whether the last node is adjacent to node 0.
+    pub ring: bool,
+    /// The shortcuts leaving each node. There is always at least one node.
+    pub shortcuts: Shortcuts,
+}
+
+impl Instance {
+    /// The number of nodes in the instance.
+    pub fn len(&self) -> usize {
+        self.shortcuts.len()
+    }
+
+    /// Whether the instance has no nodes. This is never the case for parsed instances.
+    pub fn is_empty(&self) -> bool {
+        self.shortcuts.is_empty()
+    }
+
+    /// The line or ring of the instance, with its walking costs and directions, for the search
+    /// engines to walk along.
+    pub fn line(&self) -> Line {
+        let walking = self.walking.clone();
+        let line = if self.ring {
+            Line::ring_with_walking_costs(walking)
+        } else {
+            Line::with_walking_costs(walking)
+        };
+        line.with_directions(self.directions.clone())
+    }
+
+    /// The largest walking or shortcut cost of the instance, or 0 if there are no costs at all.
+    pub fn max_cost(&self) -> usize {
+        let shortcuts = self
+            .shortcuts
+            .shortcuts
+            .iter()
+            .map(|shortcut| shortcut.cost);
+        self.walking
+            .iter()
+            .copied()
+            .chain(shortcuts)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// The reasons why an instance can fail to parse.
+///
+/// Lines and columns are 1-indexed, and columns count bytes from the start of the line.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input could not be read.
+    Io(io::Error),
+    /// The input ended before `line`, where more numbers were expected.
+    UnexpectedEnd { line: usize },
+    /// The token at the given position is not a valid number.
+    InvalidNumber {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    /// The number of nodes is 0.
+    NoNodes { line: usize },
+    /// The number of nodes `n` is too large for an instance to ever fit in memory.
+    TooManyNodes { line: usize, n: usize },
+    /// A shortcut does not point to a node between 1 and `n`.
+    ShortcutOutOfRange {
+        line: usize,
+        column: usize,
+        value: usize,
+        n: usize,
+    },
+    /// The input ended before there was one shortcut per node. `line` is where the shortcuts start.
+    ShortcutCount {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The input ended before all the walking or shortcut costs. `line` is where the costs start.
+    CostCount {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A node of the edge list or of a query is not between 1 and `n`.
+    NodeOutOfRange {
+        line: usize,
+        column: usize,
+        value: usize,
+        n: usize,
+    },
+    /// A line has more numbers than the shape described by `expected` allows.
+    MalformedLine {
+        line: usize,
+        expected: &'static str,
+        found: usize,
+    },
+    /// The token at the given position is not a valid direction.
+    InvalidDirection {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    /// A number or the end of the input came before there was one direction per segment. `line` is
+    /// where the directions start.
+    DirectionCount {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The input ended before there was one distance per node. `line` is where the distances start.
+    DistanceCount {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// There is more input after everything that was expected.
+    TrailingInput { line: usize, column: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "could not read input: {}", err),
+            ParseError::UnexpectedEnd { line } => {
+                write!(f, "line {}: unexpected end of input", line)
+            }
+            ParseError::InvalidNumber {
+                line,
+                column,
+                token,
+            } if token.is_empty() => {
+                write!(f, "line {}, column {}: expected a number", line, column)
+            }
+            ParseError::InvalidNumber {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: expected a number, found `{}`",
+                line, column, token
+            ),
+            ParseError::NoNodes { line } => {
+                write!(f, "line {}: there must be at least one node", line)
+            }
+            ParseError::TooManyNodes { line, n } => {
+                write!(f, "line {}: {} nodes are too many to solve", line, n)
+            }
+            ParseError::ShortcutOutOfRange {
+                line,
+                column,
+                value,
+                n,
+            } => write!(
+                f,
+                "line {}, column {}: shortcut {} is not between 1 and {}",
+                line, column, value, n
+            ),
+            ParseError::ShortcutCount {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {} shortcuts, found {}",
+                line, expected, found
+            ),
+            ParseError::CostCount {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {} costs, found {}",
+                line, expected, found
+            ),
+            ParseError::NodeOutOfRange {
+                line,
+                column,
+                value,
+                n,
+            } => write!(
+                f,
+                "line {}, column {}: node {} is not between 1 and {}",
+                line, column, value, n
+            ),
+            ParseError::MalformedLine {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {}, found {} values",
+                line, expected, found
+            ),
+            ParseError::InvalidDirection {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: expected a direction (`=`, `>`, `<` or `x`), found `{}`",
+                line, column, token
+            ),
+            ParseError::DirectionCount {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {} directions, found {}",
+                line, expected, found
+            ),
+            ParseError::DistanceCount {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {} distances, found {}",
+                line, expected, found
+            ),
+            ParseError::TrailingInput { line, column } => write!(
+                f,
+                "line {}, column {}: expected the end of the input",
+                line, column
+            ),
+        }
+    }
+}
+
+impl error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ParseError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ParseError {
+    fn from(err: io::Error) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+// Parse a single token starting at the given (0-indexed) byte offset of its line.
+//
+// This accepts the same numbers as `str::parse`, without going through a string.
+fn number(token: &[u8], line: usize, offset: usize) -> Result<usize, ParseError> {
+    let digits = token.strip_prefix(b"+").unwrap_or(token);
+    if digits.is_empty() || digits.len() > MAX_DIGITS {
+        return token_number(token, line, offset);
+    }
+
+    // Short enough tokens cannot overflow, so we only need to check that they are all digits.
+    let mut value = 0;
+    for &byte in digits {
+        let digit = byte.wrapping_sub(b'0');
+        if digit > 9 {
+            return Err(invalid(token, line, offset));
+        }
+        value = value * 10 + digit as usize;
+    }
+
+    Ok(value)
+}
+
+// The number of digits that always fit in a `usize`.
+const MAX_DIGITS: usize = (usize::MAX.ilog10()) as usize;
+
+// Parse a token that may overflow, or that is not a number at all.
+fn token_number(token: &[u8], line: usize, offset: usize) -> Result<usize, ParseError> {
+    std::str::from_utf8(token)
+        .ok()
+        .and_then(|token| token.parse().ok())
+        .ok_or_else(|| invalid(token, line, offset))
+}
+
+// The error for a `token` that is not a valid number.
+fn invalid(token: &[u8], line: usize, offset: usize) -> ParseError {
+    ParseError::InvalidNumber {
+        line,
+        column: offset + 1,
+        token: String::from_utf8_lossy(token).into_owned(),
+    }
+}
+
+// The largest number of items to make room for before reading them. Counts come from the input
+// and can be arbitrarily large, so reserving space for all the items they announce could abort on
+// a short input that would otherwise be reported as ending too early.
+const MAX_PREALLOCATION: usize = 1 << 16;
+
+// The largest number of nodes of an instance. Solving an instance takes several arrays with an
+// entry of up to 64 bytes per node, which could not be addressed with more nodes than this.
+const MAX_NODES: usize = isize::MAX as usize / 64;
+
+// A number along with the (1-indexed) column where it was found.
+type Located = (usize, usize);
+
+// Convert a 1-indexed node found at the given position into a 0-indexed node.
+fn node((value, column): Located, line: usize, n: usize) -> Result<usize, ParseError> {
+    if value == 0 || value > n {
+        Err(ParseError::NodeOutOfRange {
+            line,
+            column,
+            value,
+            n,
+        })
+    } else {
+        Ok(value - 1)
+    }
+}
+
+/// Reads consecutive items from an input, keeping track of line numbers for error reporting.
+///
+/// This is needed when the input contains more than just an instance, such as the queries that
+/// follow it; otherwise [`parse`] is more convenient.
+///
+/// Numbers are parsed directly from the buffer of the underlying reader, so that nothing is copied
+/// or allocated for each line or token. The only exception is a token that crosses the end of that
+/// buffer, which is gathered into a small buffer of its own.
+#[derive(Debug)]
+pub struct Reader<R> {
+    reader: R,
+    // The token at the current position, if it crossed the end of the reader's buffer.
+    scratch: Vec<u8>,
+    spilled: bool,
+    // The (1-indexed) line of the next unread byte, and its (0-indexed) position in that line.
+    line: usize,
+    column: usize,
+    ring: bool,
+}
+
+impl<R: BufRead> Reader<R> {
+    /// Create a reader starting at the first line of `reader`.
+    pub fn new(reader: R) -> Self {
+        Reader {
+            reader,
+            scratch: Vec::new(),
+            spilled: false,
+            line: 1,
+            column: 0,
+            ring: false,
+        }
+    }
+
+    /// Read the following instances as rings, where the last node is adjacent to the first one.
+    pub fn rings(mut self, ring: bool) -> Self {
+        self.ring = ring;
+        self
+    }
+
+    // The number of segments between the n nodes of an instance.
+    fn segments(&self, n: usize) -> usize {
+        if self.ring {
+            n
+        } else {
+            n - 1
+        }
+    }
+
+    // Move to the start of the next token, reading more input as needed. Returns false if the
+    // input ends first.
+    fn skip_whitespace(&mut self) -> Result<bool, ParseError> {
+        if self.spilled {
+            return Ok(true);
+        }
+
+        loop {
+            let buffer = self.reader.fill_buf()?;
+            if buffer.is_empty() {
+                return Ok(false);
+            }
+
+            let found = buffer.iter().position(|byte| !byte.is_ascii_whitespace());
+            let skipped = found.unwrap_or(buffer.len());
+            for &byte in &buffer[..skipped] {
+                if byte == b'\n' {
+                    self.line += 1;
+                    self.column = 0;
+                } else {
+                    self.column += 1;
+                }
+            }
+
+            self.reader.consume(skipped);
+            if found.is_some() {
+                return Ok(true);
+            }
+        }
+    }
+
+    // The (1-indexed) line where the input ends, after the last one if it ends with a line break.
+    fn end_line(&self) -> usize {
+        self.line + usize::from(self.column > 0)
+    }
+
+    // The token starting at the current position, which must not be whitespace. It is left unread
+    // until `advance` moves past it.
+    fn token(&mut self) -> Result<&[u8], ParseError> {
+        if !self.spilled {
+            // The buffer is looked up again to return the token, as returning this borrow would
+            // extend it over the rest of the function.
+            let buffer = self.reader.fill_buf()?;
+            if let Some(len) = buffer.iter().position(u8::is_ascii_whitespace) {
+                return Ok(&self.reader.fill_buf()?[..len]);
+            }
+
+            self.scratch.clear();
+            loop {
+                let buffer = self.reader.fill_buf()?;
+                let found = buffer.iter().position(u8::is_ascii_whitespace);
+                let len = found.unwrap_or(buffer.len());
+                self.scratch.extend_from_slice(&buffer[..len]);
+                self.reader.consume(len);
+                if found.is_some() || len == 0 {
+                    break;
+                }
+            }
+            self.spilled = true;
+        }
+
+        Ok(&self.scratch)
+    }
+
+    // Move past the `len` bytes of the token at the current position.
+    fn advance(&mut self, len: usize) {
+        if self.spilled {
+            self.spilled = false;
+        } else {
+            self.reader.consume(len);
+        }
+        self.column += len;
+    }
+
+    // Parse the next number, returning it with the line and column where it was found, or `None`
+    // if the input ends first.
+    fn next_number(&mut self) -> Result<Option<(usize, Located)>, ParseError> {
+        if !self.skip_whitespace()? {
+            return Ok(None);
+        }
+
+        let (line, column) = (self.line, self.column);
+        let token = self.token()?;
+        let len = token.len();
+        let value = number(token, line, column)?;
+        self.advance(len);
+        Ok(Some((line, (value, column + 1))))
+    }
+
+    // Parse the next number, which must be there.
+    fn number(&mut self) -> Result<(usize, Located), ParseError> {
+        match self.next_number()? {
+            Some(number) => Ok(number),
+            None => Err(ParseError::UnexpectedEnd {
+                line: self.end_line(),
+            }),
+        }
+    }
+
+    // Parse the next `count` numbers, passing each of them to `f` with its line and column. If the
+    // input ends first, `short` builds the error from the line of the first number (or the end of
+    // the input if there are none) and the number of numbers found.
+    fn numbers(
+        &mut self,
+        count: usize,
+        short: impl FnOnce(usize, usize) -> ParseError,
+        mut f: impl FnMut(usize, Located) -> Result<(), ParseError>,
+    ) -> Result<(), ParseError> {
+        let mut first = None;
+        for found in 0..count {
+            match self.next_number()? {
+                Some((line, value)) => {
+                    first.get_or_insert(line);
+                    f(line, value)?;
+                }
+                None => return Err(short(first.unwrap_or(self.end_line()), found)),
+            }
+        }
+
+        Ok(())
+    }
+
+    // Parse the directions of the `count` segments of the line if they are there, which is the case
+    // if the next token is a direction. Otherwise, all the segments are two-way, which is left to
+    // the caller so that nothing is allocated for them before the rest of the instance is read.
+    fn directions(&mut self, count: usize) -> Result<Option<Vec<Direction>>, ParseError> {
+        let mut directions = Vec::new();
+        let mut first = None;
+        while directions.len() < count && self.skip_whitespace()? {
+            let (line, column) = (self.line, self.column);
+            let token = self.token()?;
+            let len = token.len();
+            let direction = match token {
+                b"=" => Direction::Both,
+                b">" => Direction::Forward,
+                b"<" => Direction::Backward,
+                b"x" => Direction::Closed,
+                // Anything else is left for the numbers that follow to report, unless it comes
+                // after some directions and cannot be a number either.
+                [b'0'..=b'9' | b'+', ..] => break,
+                _ if first.is_none() => break,
+                _ => {
+                    return Err(ParseError::InvalidDirection {
+                        line,
+                        column: column + 1,
+                        token: String::from_utf8_lossy(token).into_owned(),
+                    })
+                }
+            };
+
+            first.get_or_insert(line);
+            self.advance(len);
+            directions.push(direction);
+        }
+
+        match (first, directions.len()) {
+            (None, _) => Ok(None),
+            (Some(_), found) if found == count => Ok(Some(directions)),
+            (Some(line), found) => Err(ParseError::DirectionCount {
+                line,
+                expected: count,
+                found,
+            }),
+        }
+    }
+
+    // Parse `count` costs.
+    fn costs(&mut self, count: usize) -> Result<Vec<usize>, ParseError> {
+        let mut costs = Vec::with_capacity(count.min(MAX_PREALLOCATION));
+        let short = |line, found| ParseError::CostCount {
+            line,
+            expected: count,
+            found,
+        };
+        self.numbers(count, short, |_, (cost, _)| {
+            costs.push(cost);
+            Ok(())
+        })?;
+
+        Ok(costs)
+    }
+
+    // Parse the number of nodes of an instance.
+    fn node_count(&mut self) -> Result<usize, ParseError> {
+        let (line, (n, _)) = self.number()?;
+        if n == 0 {
+            return Err(ParseError::NoNodes { line });
+        }
+        if n > MAX_NODES {
+            return Err(ParseError::TooManyNodes { line, n });
+        }
+
+        Ok(n)
+    }
+
+    // Parse an instance in the edge list format.
+    fn edges(&mut self) -> Result<Instance, ParseError> {
+        let n = self.node_count()?;
+
+        let (_, (m, _)) = self.number()?;
+        let segments = self.segments(n);
+        let directions = self.directions(segments)?;
+        let mut edges = Vec::with_capacity(m.min(MAX_PREALLOCATION));
+        for _ in 0..m {
+            let (line, u) = self.number()?;
+            let (line_v, v) = self.number()?;
+
+            // The cost is optional, so it can only be told apart from the next edge by being on
+            // the same line, and nothing else can follow it there.
+            let mut cost = 1;
+            if self.skip_whitespace()? && self.line == line_v {
+                (_, (cost, _)) = self.number()?;
+                if self.skip_whitespace()? && self.line == line_v {
+                    let mut found = 3;
+                    while self.skip_whitespace()? && self.line == line_v {
+                        let len = self.token()?.len();
+                        self.advance(len);
+                        found += 1;
+                    }
+                    return Err(ParseError::MalformedLine {
+                        line: line_v,
+                        expected: "`u v` or `u v w`",
+                        found,
+                    });
+                }
+            }
+
+            let to = node(v, line_v, n)?;
+            edges.push((node(u, line, n)?, Shortcut { to, cost }));
+        }
+
+        Ok(Instance {
+            walking: vec![1; segments],
+            directions: directions.unwrap_or_else(|| vec![Direction::Both; segments]),
+            ring: self.ring,
+            shortcuts: Shortcuts::from_edges(n, &edges),
+        })
+    }
+
+    /// Parse the next instance, in the given `format`.
+    pub fn instance(&mut self, format: Format) -> Result<Instance, ParseError> {
+        if format == Format::Edges {
+            return self.edges();
+        }
+
+        let n = self.node_count()?;
+
+        // Walking costs are only read for weighted instances. Other instances have unit costs,
+        // which are only filled in once the shortcuts have confirmed that there are n nodes.
+        let segments = self.segments(n);
+        let walking = match format {
+            Format::Weighted => Some(self.costs(segments)?),
+            _ => None,
+        };
+        let directions = self.directions(segments)?;
+
+        // We subtract 1 from the shortcut index because that works better with 0-indexed arrays.
+        let mut targets = Vec::with_capacity(n.min(MAX_PREALLOCATION));
+        let short = |line, found| ParseError::ShortcutCount {
+            line,
+            expected: n,
+            found,
+        };
+        self.numbers(n, short, |line, (value, column)| {
+            if value == 0 || value > n {
+                return Err(ParseError::ShortcutOutOfRange {
+                    line,
+                    column,
+                    value,
+                    n,
+                });
+            }
+
+            targets.push(value - 1);
+            Ok(())
+        })?;
+
+        let shortcut_costs = match format {
+            Format::Weighted => self.costs(n)?,
+            _ => vec![1; n],
+        };
+
+        let shortcuts = targets
+            .into_iter()
+            .zip(shortcut_costs)
+            .map(|(to, cost)| Shortcut { to, cost })
+            .collect();
+
+        Ok(Instance {
+            walking: walking.unwrap_or_else(|| vec![1; segments]),
+            directions: directions.unwrap_or_else(|| vec![Direction::Both; segments]),
+            ring: self.ring,
+            shortcuts: Shortcuts::one_per_node(shortcuts),
+        })
+    }
+
+    /// Parse the number of test cases that follow.
+    pub fn case_count(&mut self) -> Result<usize, ParseError> {
+        let (_, (t, _)) = self.number()?;
+        Ok(t)
+    }
+
+    /// Parse a list of queries on an instance with `n` nodes.
+    ///
+    /// The list starts with the number of queries `q`, followed by `q` pairs `s t` asking for the
+    /// distance from node `s` to node `t`, normally on a line each. Nodes are converted to
+    /// 0-indexed nodes.
+    pub fn queries(&mut self, n: usize) -> Result<Vec<(usize, usize)>, ParseError> {
+        let (_, (q, _)) = self.number()?;
+
+        let mut queries = Vec::with_capacity(q.min(MAX_PREALLOCATION));
+        for _ in 0..q {
+            let (line_s, s) = self.number()?;
+            let (line_t, t) = self.number()?;
+            queries.push((node(s, line_s, n)?, node(t, line_t, n)?));
+        }
+
+        Ok(queries)
+    }
+
+    /// Parse a candidate answer for an instance with `n` nodes: the distance to each node, or `-1`
+    /// for the nodes that cannot be reached, as written by the plain output format.
+    pub fn distances(&mut self, n: usize) -> Result<Vec<Option<usize>>, ParseError> {
+        let mut distances = Vec::with_capacity(n);
+        let mut first = None;
+        while distances.len() < n && self.skip_whitespace()? {
+            let (line, column) = (self.line, self.column);
+            let token = self.token()?;
+            let len = token.len();
+            let distance = match token {
+                b"-1" => None,
+                _ => Some(number(token, line, column)?),
+            };
+
+            first.get_or_insert(line);
+            self.advance(len);
+            distances.push(distance);
+        }
+
+        if distances.len() < n {
+            return Err(ParseError::DistanceCount {
+                line: first.unwrap_or(self.end_line()),
+                expected: n,
+                found: distances.len(),
+            });
+        }
+
+        Ok(distances)
+    }
+
+    /// Check that there is nothing left to read but whitespace, after the last instance or
+    /// answer.
+    pub fn end(&mut self) -> Result<(), ParseError> {
+        if self.skip_whitespace()? {
+            return Err(ParseError::TrailingInput {
+                line: self.line,
+                column: self.column + 1,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse an instance in the given `format` from `reader`.
+///
+/// The instance must make up the whole input: anything but whitespace after it is an error, such as
+/// a shortcut too many.
+pub fn parse(reader: impl BufRead, format: Format) -> Result<Instance, ParseError> {
+    let mut reader = Reader::new(reader);
+    let instance = reader.instance(format)?;
+    reader.end()?;
+    Ok(instance)
+}
@@ -0,0 +1,1194 @@
+//! A shortest-path engine for graphs built on top of a bi-directional line.
+//!
+//! The graph always contains the nodes `0 .. n` with edges between each node `i` and its
+//! neighbours `i - 1` and `i + 1`. Any additional edge (a "shortcut") is supplied by the user while
+//! the search is running: every time [`LinearDijkstra::pop`] returns a node, the caller pushes the
+//! shortcuts leaving that node with [`LinearDijkstra::push`].
+//!
+//! The line can also be closed into a ring, where the last node is adjacent to node 0, and its
+//! segments can be given their own costs and directions; see [`Line`]. The same search also runs
+//! on two-dimensional [`grid`]s with [`GridDijkstra`], and on any other [`Topology`] with
+//! [`Dijkstra`].
+//!
+//! ```
+//! use symmetrical_palm_tree::{LinearDijkstra, State, Step, StepKind};
+//!
+//! // Node i has a shortcut to shortcuts[i].
+//! let shortcuts = [3, 3, 3, 3, 6, 6, 6];
+//!
+//! let mut solver = LinearDijkstra::new(shortcuts.len());
+//! while let Some(State { cost, position }) = solver.pop() {
+//!     solver.push(cost + 1, Step::shortcut(position, shortcuts[position]));
+//! }
+//!
+//! let distances = solver.distances().iter().map(|d| d.unwrap()).collect::<Vec<_>>();
+//! assert_eq!(distances, [0, 1, 2, 1, 2, 3, 3]);
+//!
+//! // The route to node 5 takes the shortcut from 0 to 3, then walks twice to the right.
+//! let kinds = solver.path_to(5).unwrap().iter().map(|step| step.kind).collect::<Vec<_>>();
+//! assert_eq!(kinds, [StepKind::Shortcut, StepKind::WalkRight, StepKind::WalkRight]);
+//! ```
+//!
+//! [`LinearDijkstra`] works with arbitrary non-negative costs. When all costs are 0 or 1, the
+//! faster [`LinearBfs`] computes the same distances; both implement the [`Engine`] trait, so code
+//! driving the search can be written once for either of them. The priority queue used by
+//! [`LinearDijkstra`] can also be replaced by one of the integer [`queue`]s.
+//!
+//! Costs are `usize` by default, but topologies can use any other [`Cost`] type, such as `u32`,
+//! `u128` or [`cost::Float`]. Nodes that cannot be reached have no distance at all, rather than
+//! some sentinel value.
+//!
+//! Instances in the textual input format can be read with [`input::parse`], and the computed
+//! distances written out with an [`output::Writer`]. The distances of small instances can be
+//! checked against the brute-force [`reference`](mod@reference) solver.
+
+pub mod adjacency;
+pub mod cost;
+pub mod generate;
+pub mod grid;
+pub mod input;
+pub mod output;
+pub mod queue;
+pub mod random;
+pub mod reference;
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt;
+
+use cost::Cost;
+use queue::PriorityQueue;
+
+/// The default modulus for counting routes, see [`LinearDijkstra::path_counts`].
+pub const PATH_COUNT_MODULUS: usize = 1_000_000_007;
+
+/// A State represents a potential path from a source node to the node `position` with cost `cost`,
+/// where the cost represents the energy.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct State<C = usize> {
+    pub cost: C,
+    pub position: usize,
+}
+
+impl<C: Ord> Ord for State<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // NB: we want a min-heap, not a max-heap, so we need to flip the `cost` order.
+        other
+            .cost
+            .cmp(&self.cost)
+            .then_with(|| self.position.cmp(&other.position))
+    }
+}
+
+impl<C: Ord> PartialOrd for State<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The different ways of moving from one node to another.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum StepKind {
+    /// Walking from node `i` to node `i - 1`.
+    WalkLeft,
+    /// Walking from node `i` to node `i + 1`.
+    WalkRight,
+    /// Walking along any other implicit edge, such as between the cells of a
+    /// [`Grid`](grid::Grid) or along an edge of an [`Adjacency`](adjacency::Adjacency) list.
+    Walk,
+    /// Taking a user-supplied shortcut.
+    Shortcut,
+}
+
+impl fmt::Display for StepKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StepKind::WalkLeft => "walk left",
+            StepKind::WalkRight => "walk right",
+            StepKind::Walk => "walk",
+            StepKind::Shortcut => "shortcut",
+        })
+    }
+}
+
+/// A single edge of a path, going from node `from` to node `to`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Step {
+    pub from: usize,
+    pub to: usize,
+    pub kind: StepKind,
+}
+
+/// The directions in which a segment of the line, between nodes `i` and `i + 1`, can be walked.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Direction {
+    /// The segment can be walked both ways. This is the default.
+    #[default]
+    Both,
+    /// The segment can only be walked from `i` to `i + 1`.
+    Forward,
+    /// The segment can only be walked from `i + 1` to `i`.
+    Backward,
+    /// The segment cannot be walked at all.
+    Closed,
+}
+
+impl Direction {
+    /// Whether the segment can be walked from `i` to `i + 1`.
+    pub fn forward(self) -> bool {
+        matches!(self, Direction::Both | Direction::Forward)
+    }
+
+    /// Whether the segment can be walked from `i + 1` to `i`.
+    pub fn backward(self) -> bool {
+        matches!(self, Direction::Both | Direction::Backward)
+    }
+}
+
+/// The implicit edges of a graph, which a [`Dijkstra`] solver walks along on its own, leaving only
+/// the shortcuts to the user.
+///
+/// This crate provides a [`Line`], which is the default and can be closed into a ring, 2D
+/// [`Grid`](grid::Grid)s and explicit [`Adjacency`](adjacency::Adjacency) lists, but any other
+/// graph whose edges can be listed from each node can implement it.
+pub trait Topology {
+    /// The type of the walking costs, and therefore of the distances.
+    type Cost: Cost;
+
+    /// The number of nodes, which are the integers 0 to `len() - 1`.
+    fn len(&self) -> usize;
+
+    /// Whether there are no nodes.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The steps that can be walked from `position`, each with its own cost.
+    ///
+    /// Each step should go from `position` to a node of the graph; its kind only matters for
+    /// reporting routes.
+    fn neighbours(&self, position: usize) -> impl Iterator<Item = (Step, Self::Cost)>;
+}
+
+/// The line of nodes over which the search engines run, with the cost and the directions of each
+/// of its segments.
+///
+/// A line with n nodes has a segment between each node `i` and `i + 1`, for a total of `n - 1`
+/// segments. A ring also has an `n`-th segment between `n - 1` and 0: walking forward from `n - 1`
+/// leads to 0, and walking backward from 0 leads to `n - 1`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Line<C = usize> {
+    // walking[i] is the cost of walking along segment i, from i to i + 1 or from i + 1 to i.
+    walking: Vec<C>,
+    // The directions in which each segment can be walked, with the same indices as `walking`.
+    directions: Vec<Direction>,
+    ring: bool,
+}
+
+impl<C: Cost> Line<C> {
+    /// A line of `n` nodes, where all segments cost 1 and can be walked both ways.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    pub fn new(n: usize) -> Self {
+        assert!(n > 0, "a line needs at least one node");
+
+        Self::with_walking_costs(vec![C::ONE; n - 1])
+    }
+
+    /// A line where walking between i and i + 1 costs `walking[i]`. The line has
+    /// `walking.len() + 1` nodes.
+    pub fn with_walking_costs(walking: Vec<C>) -> Self {
+        Line {
+            directions: vec![Direction::Both; walking.len()],
+            walking,
+            ring: false,
+        }
+    }
+
+    /// A ring of `n` nodes, where all segments cost 1 and can be walked both ways.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    pub fn ring(n: usize) -> Self {
+        Self::ring_with_walking_costs(vec![C::ONE; n])
+    }
+
+    /// A ring where walking between i and i + 1 costs `walking[i]`, and walking between the last
+    /// node and 0 costs the last element of `walking`. The ring has `walking.len()` nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `walking` is empty.
+    pub fn ring_with_walking_costs(walking: Vec<C>) -> Self {
+        assert!(!walking.is_empty(), "a ring needs at least one node");
+
+        Line {
+            directions: vec![Direction::Both; walking.len()],
+            walking,
+            ring: true,
+        }
+    }
+
+    /// Restrict the directions in which each segment can be walked: `directions[i]` applies to
+    /// the segment between i and i + 1 (or 0, for the last segment of a ring).
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not exactly one direction per segment.
+    pub fn with_directions(mut self, directions: Vec<Direction>) -> Self {
+        assert_eq!(
+            directions.len(),
+            self.walking.len(),
+            "there must be one direction per segment"
+        );
+
+        self.directions = directions;
+        self
+    }
+
+    /// The number of nodes.
+    pub fn len(&self) -> usize {
+        if self.ring {
+            self.walking.len()
+        } else {
+            self.walking.len() + 1
+        }
+    }
+
+    /// Whether there are no nodes, which is never the case.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Whether the last node is adjacent to node 0.
+    pub fn is_ring(&self) -> bool {
+        self.ring
+    }
+
+    /// The cost of walking along each segment.
+    pub fn walking_costs(&self) -> &[C] {
+        &self.walking
+    }
+
+    /// The directions in which each segment can be walked.
+    pub fn directions(&self) -> &[Direction] {
+        &self.directions
+    }
+}
+
+impl<C: Cost> Topology for Line<C> {
+    type Cost = C;
+
+    fn len(&self) -> usize {
+        Line::len(self)
+    }
+
+    // The neighbours are position + 1 and position - 1, wrapping around for a ring.
+    fn neighbours(&self, position: usize) -> impl Iterator<Item = (Step, C)> {
+        let n = self.len();
+
+        // We can move forward along the segment starting at position if we are not at the end of
+        // a line, and the segment allows it
+        let forward = (position + 1 < n || self.ring)
+            .then_some(position)
+            .filter(|&segment| self.directions[segment].forward())
+            .map(|segment| {
+                let step = Step {
+                    from: position,
+                    to: (position + 1) % n,
+                    kind: StepKind::WalkRight,
+                };
+                (step, self.walking[segment])
+            });
+
+        // We can move backward along the segment ending at position if we are not at the start of
+        // a line, and the segment allows it
+        let backward = (position > 0 || self.ring)
+            .then_some((position + n - 1) % n)
+            .filter(|&segment| self.directions[segment].backward())
+            .map(|segment| {
+                let step = Step {
+                    from: position,
+                    to: segment,
+                    kind: StepKind::WalkLeft,
+                };
+                (step, self.walking[segment])
+            });
+
+        forward.into_iter().chain(backward)
+    }
+}
+
+impl Step {
+    /// A step taking the shortcut from `from` to `to`.
+    pub fn shortcut(from: usize, to: usize) -> Self {
+        Step {
+            from,
+            to,
+            kind: StepKind::Shortcut,
+        }
+    }
+}
+
+/// The operations shared by all the search engines of this crate.
+///
+/// All engines compute the same distances and routes over the implicit edges of their
+/// [`Topology`], with the same push/pop protocol as [`Dijkstra`]: they only differ in the order in
+/// which they examine potential paths, and in the costs they support.
+pub trait Engine {
+    /// The type of the costs and distances.
+    type Cost: Cost;
+
+    /// The implicit edges the engine walks along.
+    type Topology: Topology<Cost = Self::Cost>;
+
+    /// Discover a new potential path, reaching `step.to` with total cost `cost` by taking `step`.
+    ///
+    /// See [`Dijkstra::push`].
+    fn push(&mut self, cost: Self::Cost, step: Step);
+
+    /// Examine a potential path that could lead to an improvement.
+    ///
+    /// See [`Dijkstra::pop`].
+    fn pop(&mut self) -> Option<State<Self::Cost>>;
+
+    /// Restart the search from the `(node, initial_cost)` pairs in `sources`.
+    ///
+    /// See [`Dijkstra::reset`].
+    fn reset(&mut self, sources: &[(usize, Self::Cost)]);
+
+    /// Restart the search on a new `topology`, from the `(node, initial_cost)` pairs in
+    /// `sources`.
+    ///
+    /// See [`Dijkstra::reset_with_topology`].
+    fn reset_with_topology(&mut self, topology: Self::Topology, sources: &[(usize, Self::Cost)]);
+
+    /// Restart the search on a new `line`, from the `(node, initial_cost)` pairs in `sources`.
+    ///
+    /// This is [`reset_with_topology`](Self::reset_with_topology) for engines over a [`Line`].
+    fn reset_with_line(&mut self, line: Line, sources: &[(usize, usize)])
+    where
+        Self: Engine<Cost = usize, Topology = Line>,
+    {
+        self.reset_with_topology(line, sources);
+    }
+
+    /// The current shortest distance from the closest source to each node, or `None` for nodes
+    /// that have not been reached.
+    ///
+    /// See [`Dijkstra::distances`].
+    fn distances(&self) -> &[Option<Self::Cost>];
+
+    /// The last step of the route to `node` with the current shortest distance.
+    ///
+    /// See [`Dijkstra::predecessor`].
+    fn predecessor(&self, node: usize) -> Option<Step>;
+
+    /// The sequence of steps leading from a source to `node` with the current shortest distance.
+    ///
+    /// See [`Dijkstra::path_to`].
+    fn path_to(&self, node: usize) -> Option<Vec<Step>>;
+
+    /// The number of distinct routes to each node with the current shortest distance, modulo the
+    /// path count modulus.
+    ///
+    /// See [`Dijkstra::path_counts`].
+    fn path_counts(&self) -> &[usize];
+
+    /// Count routes modulo `modulus` from the next reset on.
+    ///
+    /// See [`Dijkstra::set_path_count_modulus`].
+    fn set_path_count_modulus(&mut self, modulus: usize);
+
+    /// Run the search until `target` is reached, and return its distance.
+    ///
+    /// See [`Dijkstra::shortest_to`].
+    fn shortest_to<F>(&mut self, target: usize, mut shortcuts: F) -> Option<Self::Cost>
+    where
+        Self: Sized,
+        F: FnMut(&mut Self, State<Self::Cost>),
+    {
+        assert!(target < self.distances().len(), "target is not a node");
+
+        while let Some(state) = self.pop() {
+            if state.position == target {
+                return Some(state.cost);
+            }
+
+            shortcuts(self, state);
+        }
+
+        None
+    }
+}
+
+// The part of a search that does not depend on the graph or on the order in which potential paths
+// are examined: the best paths found so far.
+#[derive(Clone, Debug)]
+struct Labels<C> {
+    // The dist vector maps each 0-indexed node to the current shortest distance to that node, or
+    // None if it has not been reached (yet).
+    distances: Vec<Option<C>>,
+
+    // The step through which we reached each node with its current shortest distance. Sources
+    // have no predecessor, and neither do nodes that have not been reached yet.
+    predecessors: Vec<Option<Step>>,
+
+    // The number of routes reaching each node with its current shortest distance, modulo
+    // `modulus`. Nodes that have not been reached yet have no route. A new modulus only takes over
+    // from `pending` at the next reset, so that the counts of a search all use the same one.
+    counts: Vec<usize>,
+    modulus: usize,
+    pending: usize,
+
+    // The nodes that have been reached since the last reset, so that resetting the solver after a
+    // short query does not need to go over the whole graph.
+    touched: Vec<usize>,
+}
+
+impl<C: Cost> Labels<C> {
+    fn new(n: usize) -> Self {
+        Labels {
+            distances: vec![None; n],
+            predecessors: vec![None; n],
+            counts: vec![0; n],
+            modulus: PATH_COUNT_MODULUS,
+            pending: PATH_COUNT_MODULUS,
+            touched: Vec::new(),
+        }
+    }
+
+    fn reset(&mut self) {
+        for position in self.touched.drain(..) {
+            self.distances[position] = None;
+            self.predecessors[position] = None;
+            self.counts[position] = 0;
+        }
+        self.modulus = self.pending;
+    }
+
+    // Move to a new graph with n nodes, keeping the allocations of the current one.
+    fn replace(&mut self, n: usize) {
+        self.reset();
+
+        self.distances.resize(n, None);
+        self.predecessors.resize(n, None);
+        self.counts.resize(n, 0);
+    }
+
+    // Record a path to `position` with total cost `cost` through `predecessor` if it is shorter
+    // than the current one, and return whether it was.
+    //
+    // The routes through `predecessor` are those reaching its origin, or only the empty route for
+    // sources. They replace the current routes to `position` if the path is shorter, and are added
+    // to them if it is just as short.
+    fn improve(&mut self, cost: C, position: usize, predecessor: Option<Step>) -> bool {
+        let distance = self.distances[position];
+        if distance.is_some_and(|distance| cost > distance) {
+            return false;
+        }
+
+        let routes = predecessor.map_or(1 % self.modulus, |step| self.counts[step.from]);
+
+        if distance == Some(cost) {
+            // Ties between sources are the same empty route.
+            if predecessor.is_some() {
+                let count = self.counts[position];
+                self.counts[position] = if count >= self.modulus - routes {
+                    count - (self.modulus - routes)
+                } else {
+                    count + routes
+                };
+            }
+            return false;
+        }
+
+        if distance.is_none() {
+            self.touched.push(position);
+        }
+
+        self.distances[position] = Some(cost);
+        self.predecessors[position] = predecessor;
+        self.counts[position] = routes;
+        true
+    }
+
+    fn set_modulus(&mut self, modulus: usize) {
+        assert!(modulus > 0, "the path count modulus must be positive");
+        self.pending = modulus;
+    }
+
+    // The cost of walking `walk` further from a path of cost `cost` to `position`, or `None` if it
+    // cannot be represented. Such a path can be dropped when `position` already has a distance,
+    // since it cannot improve on it, but its distance might not be representable otherwise.
+    fn extend(&self, cost: C, walk: C, position: usize) -> Option<C> {
+        let extended = cost.checked_add(walk);
+        assert!(
+            extended.is_some() || self.distances[position].is_some(),
+            "path cost overflow"
+        );
+        extended
+    }
+
+    // Whether we have already found a shorter path than `state` to its node.
+    fn is_stale(&self, state: State<C>) -> bool {
+        self.distances[state.position].is_some_and(|distance| state.cost > distance)
+    }
+
+    fn path_to(&self, node: usize) -> Option<Vec<Step>> {
+        self.distances[node]?;
+
+        // Walk back the predecessors until we reach a source, which has none.
+        let mut path = Vec::new();
+        let mut position = node;
+        while let Some(step) = self.predecessors[position] {
+            path.push(step);
+            position = step.from;
+        }
+
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// This is a helper struct that allows to compute Dijkstra's shortest-path on a graph whose edges
+/// are mostly implicit.
+///
+/// Conceptually, the graph is pre-populated with the nodes and the edges of its [`Topology`],
+/// which the solver walks along on its own. On a [`LinearDijkstra`], these are the elements 0 .. n
+/// with bi-directional edges from i to i + 1 and i - 1. Walking along the segment between i and
+/// i + 1 costs 1 in either direction by default, but each segment can be given its own cost with
+/// [`with_walking_costs`](LinearDijkstra::with_walking_costs). More generally, the solver can run
+/// over any [`Line`] with [`with_topology`](Self::with_topology): its segments can be made
+/// one-way, or closed altogether, in which case some nodes may not be reachable, and its last node
+/// can be connected back to node 0 to form a ring. On a [`GridDijkstra`], they are the cells of a
+/// [`Grid`](grid::Grid) and the moves between adjacent cells instead, and any other [`Topology`]
+/// such as explicit [`Adjacency`](adjacency::Adjacency) lists can be used in the same way.
+///
+/// The search on a line starts from node 0 by default. It can instead start from any node with
+/// [`with_start`](LinearDijkstra::with_start), or from several sources at once with
+/// [`with_sources`](LinearDijkstra::with_sources), in which case each node ends up with its
+/// distance to the closest source.
+///
+/// Additional edges can be added by the user and are handled through a combination of the
+/// [`pop`](Self::pop) and [`push`](Self::push) methods.
+///
+/// Calling `pop()` advances the search, and returns a potential path to examine. The user should
+/// push any possible transition to the corresponding node, which will be added to the solver if
+/// it improves its current cost.
+///
+/// Once `pop()` returns `None`, the solver has examined all potential paths from the sources to
+/// any other position, and [`distances`](Self::distances) holds the final result. The
+/// route achieving each distance can be recovered with [`path_to`](Self::path_to).
+///
+/// The solver also counts the distinct routes achieving each distance, which are available from
+/// [`path_counts`](Self::path_counts). As their number can grow exponentially, they are counted
+/// modulo a prime, [`PATH_COUNT_MODULUS`] by default.
+///
+/// When only the distance to a single node is needed, [`shortest_to`](Self::shortest_to) stops the
+/// search as soon as that node is reached. The same solver can then be [`reset`](Self::reset) to
+/// answer another query without reallocating its buffers.
+///
+/// Potential paths are kept in a [`BinaryHeap`] by default, which works for any costs. Other
+/// [`PriorityQueue`]s can be used instead with
+/// [`with_queue_and_topology`](Self::with_queue_and_topology), such as the
+/// [`BucketQueue`](queue::BucketQueue) or the [`RadixHeap`](queue::RadixHeap) that are faster for
+/// small integer costs.
+#[derive(Clone, Debug)]
+pub struct Dijkstra<T: Topology = Line, Q = BinaryHeap<State<<T as Topology>::Cost>>> {
+    topology: T,
+
+    labels: Labels<T::Cost>,
+
+    // The queue is used to implement a priority queue, so that we always investigate short paths
+    // before long paths (that could end up being discarded).
+    queue: Q,
+}
+
+/// A [`Dijkstra`] solver over a [`Line`].
+pub type LinearDijkstra<Q = BinaryHeap<State>> = Dijkstra<Line, Q>;
+
+/// A [`Dijkstra`] solver over a [`Grid`](grid::Grid).
+pub type GridDijkstra<Q = BinaryHeap<State>> = Dijkstra<grid::Grid, Q>;
+
+impl LinearDijkstra {
+    /// Create a new LinearDijkstra solver with n nodes representing the integers 0 to n - 1,
+    /// starting from node 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0, since there is no node to start from.
+    pub fn new(n: usize) -> Self {
+        Self::with_start(n, 0)
+    }
+
+    /// Create a new LinearDijkstra solver with n nodes, starting from node `start`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is not a node of the graph.
+    pub fn with_start(n: usize, start: usize) -> Self {
+        Self::with_sources(n, &[(start, 0)])
+    }
+
+    /// Create a new LinearDijkstra solver with n nodes, starting simultaneously from all the
+    /// `(node, initial_cost)` pairs in `sources`.
+    ///
+    /// If a node appears several times, only its lowest initial cost is kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0 or if any of the sources is not a node of the graph.
+    pub fn with_sources(n: usize, sources: &[(usize, usize)]) -> Self {
+        assert!(n > 0, "LinearDijkstra needs at least one node");
+
+        Self::with_walking_costs_and_sources(vec![1; n - 1], sources)
+    }
+
+    /// Create a new LinearDijkstra solver where walking between i and i + 1 costs `walking[i]`,
+    /// starting from node 0.
+    ///
+    /// The line has `walking.len() + 1` nodes.
+    pub fn with_walking_costs(walking: Vec<usize>) -> Self {
+        Self::with_walking_costs_and_sources(walking, &[(0, 0)])
+    }
+
+    /// Create a new LinearDijkstra solver where walking between i and i + 1 costs `walking[i]`,
+    /// starting simultaneously from all the `(node, initial_cost)` pairs in `sources`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the sources is not a node of the graph.
+    pub fn with_walking_costs_and_sources(walking: Vec<usize>, sources: &[(usize, usize)]) -> Self {
+        Self::with_queue(BinaryHeap::new(), walking, sources)
+    }
+
+    /// Create a new LinearDijkstra solver over `line`, starting simultaneously from all the
+    /// `(node, initial_cost)` pairs in `sources`.
+    ///
+    /// This is the same as [`with_topology`](Dijkstra::with_topology).
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the sources is not a node of the line.
+    pub fn with_line(line: Line, sources: &[(usize, usize)]) -> Self {
+        Self::with_topology(line, sources)
+    }
+}
+
+impl<T: Topology> Dijkstra<T> {
+    /// Create a new solver over `topology`, starting simultaneously from all the
+    /// `(node, initial_cost)` pairs in `sources`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the sources is not a node of the graph.
+    pub fn with_topology(topology: T, sources: &[(usize, T::Cost)]) -> Self {
+        Self::with_queue_and_topology(BinaryHeap::new(), topology, sources)
+    }
+}
+
+impl<Q: PriorityQueue> LinearDijkstra<Q> {
+    /// Create a new LinearDijkstra solver that keeps potential paths in `queue`, where walking
+    /// between i and i + 1 costs `walking[i]`, starting simultaneously from all the
+    /// `(node, initial_cost)` pairs in `sources`.
+    ///
+    /// `queue` is cleared before use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the sources is not a node of the graph.
+    pub fn with_queue(queue: Q, walking: Vec<usize>, sources: &[(usize, usize)]) -> Self {
+        Self::with_queue_and_topology(queue, Line::with_walking_costs(walking), sources)
+    }
+
+    /// Restart the search on a new line where walking between i and i + 1 costs `walking[i]`,
+    /// from the `(node, initial_cost)` pairs in `sources`.
+    ///
+    /// This is equivalent to creating a new solver, but reuses the buffers of this one, which
+    /// avoids reallocating them when solving many instances in a row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the sources is not a node of the new line.
+    pub fn reset_with_walking_costs(&mut self, walking: Vec<usize>, sources: &[(usize, usize)]) {
+        self.reset_with_topology(Line::with_walking_costs(walking), sources);
+    }
+
+    /// Restart the search on a new `line`, from the `(node, initial_cost)` pairs in `sources`.
+    ///
+    /// This is the same as [`reset_with_topology`](Dijkstra::reset_with_topology).
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the sources is not a node of the new line.
+    pub fn reset_with_line(&mut self, line: Line, sources: &[(usize, usize)]) {
+        self.reset_with_topology(line, sources);
+    }
+}
+
+impl<T: Topology, Q: PriorityQueue<T::Cost>> Dijkstra<T, Q> {
+    /// Create a new solver over `topology` that keeps potential paths in `queue`, starting
+    /// simultaneously from all the `(node, initial_cost)` pairs in `sources`.
+    ///
+    /// `queue` is cleared before use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the sources is not a node of the graph.
+    pub fn with_queue_and_topology(
+        mut queue: Q,
+        topology: T,
+        sources: &[(usize, T::Cost)],
+    ) -> Self {
+        queue.clear();
+
+        let mut solver = Dijkstra {
+            labels: Labels::new(topology.len()),
+            topology,
+            queue,
+        };
+
+        solver.seed(sources);
+        solver
+    }
+
+    /// Restart the search from the `(node, initial_cost)` pairs in `sources`, forgetting about any
+    /// previous search.
+    ///
+    /// This reuses the buffers of the solver, and only takes time proportional to the number of
+    /// nodes reached by the previous search.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the sources is not a node of the graph.
+    pub fn reset(&mut self, sources: &[(usize, T::Cost)]) {
+        self.labels.reset();
+        self.queue.clear();
+        self.seed(sources);
+    }
+
+    /// Restart the search on a new `topology`, from the `(node, initial_cost)` pairs in
+    /// `sources`.
+    ///
+    /// This is equivalent to creating a new solver, but reuses the buffers of this one, which
+    /// avoids reallocating them when solving many instances in a row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the sources is not a node of the new graph.
+    pub fn reset_with_topology(&mut self, topology: T, sources: &[(usize, T::Cost)]) {
+        self.labels.replace(topology.len());
+        self.topology = topology;
+        self.queue.clear();
+        self.seed(sources);
+    }
+
+    // Sources are reached without taking any step, so they are seeded without a predecessor.
+    fn seed(&mut self, sources: &[(usize, T::Cost)]) {
+        for &(position, cost) in sources {
+            if self.labels.improve(cost, position, None) {
+                self.queue.push(State { cost, position });
+            }
+        }
+    }
+
+    /// Discover a new potential path, reaching `step.to` with total cost `cost` by taking `step`.
+    ///
+    /// The new potential path is only considered if it has lower total cost than any current path
+    /// to the node, in which case `step` is recorded as the last step of the route to `step.to`.
+    ///
+    /// The cost of `step` itself is up to the caller: a shortcut with weight `w` taken from a
+    /// state with cost `cost` is pushed with a total cost of `cost + w`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step.to` is not a node of the graph.
+    pub fn push(&mut self, cost: T::Cost, step: Step) {
+        if self.labels.improve(cost, step.to, Some(step)) {
+            self.queue.push(State {
+                cost,
+                position: step.to,
+            });
+        }
+    }
+
+    /// Examine a potential path that could lead to an improvement.
+    ///
+    /// If appropriate, the neighbours of the new potential path in the topology (position - 1 and
+    /// position + 1 on a line) will automatically be added to the solver. The user should then add
+    /// any additional shortcuts that are available before calling pop again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if walking to a neighbour that has not been reached yet costs more than the cost type
+    /// can represent.
+    pub fn pop(&mut self) -> Option<State<T::Cost>> {
+        while let Some(state) = self.queue.pop() {
+            // If we have already found a shorter path to that node, we can safely skip this one.
+            if self.labels.is_stale(state) {
+                continue;
+            }
+
+            for (step, walk) in self.topology.neighbours(state.position) {
+                let Some(cost) = self.labels.extend(state.cost, walk, step.to) else {
+                    continue;
+                };
+                if self.labels.improve(cost, step.to, Some(step)) {
+                    self.queue.push(State {
+                        cost,
+                        position: step.to,
+                    });
+                }
+            }
+
+            return Some(state);
+        }
+
+        None
+    }
+
+    /// Run the search until `target` is reached, and return its distance.
+    ///
+    /// `shortcuts` is called on every examined state, and should push the additional shortcuts
+    /// leaving that state, as would be done after each call to [`pop`](Self::pop). Once `target`
+    /// is returned by `pop`, its distance is final and the search stops early: the distances and
+    /// route to `target` are then available, while other nodes may not have been examined yet.
+    ///
+    /// Returns `None` if the search ends without reaching `target`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` is not a node of the graph.
+    pub fn shortest_to<F>(&mut self, target: usize, shortcuts: F) -> Option<T::Cost>
+    where
+        F: FnMut(&mut Self, State<T::Cost>),
+    {
+        Engine::shortest_to(self, target, shortcuts)
+    }
+
+    /// The current shortest distance from the closest source to each node, or `None` for nodes
+    /// that have not been reached (yet).
+    ///
+    /// Distances are only final once [`pop`](Self::pop) has returned `None`; before that, they
+    /// are upper bounds on the true distances.
+    pub fn distances(&self) -> &[Option<T::Cost>] {
+        &self.labels.distances
+    }
+
+    /// The last step of the route to `node` with the current shortest distance.
+    ///
+    /// Returns `None` if `node` is a source that was not reached more cheaply from elsewhere, or if
+    /// it has not been reached yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` is not a node of the graph.
+    pub fn predecessor(&self, node: usize) -> Option<Step> {
+        self.labels.predecessors[node]
+    }
+
+    /// The sequence of steps leading from a source to `node` with the current shortest distance.
+    ///
+    /// Returns `None` if `node` has not been reached yet. As with [`distances`](Self::distances),
+    /// the route is only guaranteed to be optimal once [`pop`](Self::pop) has returned `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` is not a node of the graph.
+    pub fn path_to(&self, node: usize) -> Option<Vec<Step>> {
+        self.labels.path_to(node)
+    }
+
+    /// The number of distinct routes from a source to each node with the current shortest
+    /// distance, modulo the [path count modulus](Self::set_path_count_modulus). Nodes that have
+    /// not been reached yet have no route.
+    ///
+    /// Routes are told apart by the steps they take, so that a shortcut between adjacent nodes is
+    /// distinct from walking between them. Each source has a single (empty) route, even if it is
+    /// listed several times.
+    ///
+    /// Every time a path is pushed with the same cost as the current shortest path to its node,
+    /// the routes to the node being examined are added to those of its destination. Counts are
+    /// therefore final once [`pop`](Self::pop) has returned `None`, provided that all costs are
+    /// positive: routes going through steps of cost 0 may be missed, and there can be infinitely
+    /// many of them anyway.
+    pub fn path_counts(&self) -> &[usize] {
+        &self.labels.counts
+    }
+
+    /// The topology the solver runs over.
+    pub fn topology(&self) -> &T {
+        &self.topology
+    }
+
+    /// Count routes modulo `modulus` rather than [`PATH_COUNT_MODULUS`], starting from the next
+    /// call to [`reset`](Self::reset) or [`reset_with_topology`](Self::reset_with_topology).
+    ///
+    /// The modulus should be a prime, or at least large enough for the counts to be meaningful.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is 0.
+    pub fn set_path_count_modulus(&mut self, modulus: usize) {
+        self.labels.set_modulus(modulus);
+    }
+}
+
+impl<T: Topology, Q: PriorityQueue<T::Cost>> Engine for Dijkstra<T, Q> {
+    type Cost = T::Cost;
+    type Topology = T;
+
+    fn push(&mut self, cost: T::Cost, step: Step) {
+        Dijkstra::push(self, cost, step)
+    }
+
+    fn pop(&mut self) -> Option<State<T::Cost>> {
+        Dijkstra::pop(self)
+    }
+
+    fn reset(&mut self, sources: &[(usize, T::Cost)]) {
+        Dijkstra::reset(self, sources)
+    }
+
+    fn reset_with_topology(&mut self, topology: T, sources: &[(usize, T::Cost)]) {
+        Dijkstra::reset_with_topology(self, topology, sources)
+    }
+
+    fn distances(&self) -> &[Option<T::Cost>] {
+        Dijkstra::distances(self)
+    }
+
+    fn predecessor(&self, node: usize) -> Option<Step> {
+        Dijkstra::predecessor(self, node)
+    }
+
+    fn path_to(&self, node: usize) -> Option<Vec<Step>> {
+        Dijkstra::path_to(self, node)
+    }
+
+    fn path_counts(&self) -> &[usize] {
+        Dijkstra::path_counts(self)
+    }
+
+    fn set_path_count_modulus(&mut self, modulus: usize) {
+        Dijkstra::set_path_count_modulus(self, modulus)
+    }
+}
+
+/// A breadth-first search engine over the same graphs as [`Dijkstra`], for costs that are all 0 or
+/// 1.
+///
+/// When all costs are 1, potential paths are discovered in order of increasing cost, so a plain
+/// FIFO queue examines them in the right order without the overhead of a heap. When some costs
+/// are 0, this becomes a 0-1 BFS: paths that cost as much as the one being examined are put at the
+/// front of the queue rather than at the back. Either way, each operation takes constant time.
+///
+/// Sources can have arbitrary initial costs; they are examined in order of increasing cost
+/// alongside the queue.
+///
+/// The engine is used through the [`Engine`] trait, with the same protocol as [`Dijkstra`].
+/// Walking costs other than 0 or 1 are rejected with a panic as soon as the search comes across
+/// them. Pushing a path whose cost is not that of the last examined path, or one more, breaks the
+/// ordering and leads to wrong distances; this is checked in debug builds.
+#[derive(Clone, Debug)]
+pub struct Bfs<T: Topology = Line> {
+    topology: T,
+
+    labels: Labels<T::Cost>,
+
+    // The potential paths to examine, in order of increasing cost. The costs in the queue never
+    // differ by more than 1.
+    queue: VecDeque<State<T::Cost>>,
+
+    // The sources that have not been examined yet, in order of decreasing cost so that the next
+    // one can be popped from the end.
+    sources: Vec<State<T::Cost>>,
+
+    // The cost of the last examined path.
+    current: T::Cost,
+}
+
+/// A [`Bfs`] engine over a [`Line`].
+pub type LinearBfs = Bfs<Line>;
+
+impl LinearBfs {
+    /// Create a new LinearBfs engine with n nodes and unit walking costs, starting from node 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0, since there is no node to start from.
+    pub fn new(n: usize) -> Self {
+        Self::with_sources(n, &[(0, 0)])
+    }
+
+    /// Create a new LinearBfs engine with n nodes and unit walking costs, starting simultaneously
+    /// from all the `(node, initial_cost)` pairs in `sources`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0 or if any of the sources is not a node of the graph.
+    pub fn with_sources(n: usize, sources: &[(usize, usize)]) -> Self {
+        assert!(n > 0, "LinearBfs needs at least one node");
+
+        Self::with_walking_costs_and_sources(vec![1; n - 1], sources)
+    }
+
+    /// Create a new LinearBfs engine where walking between i and i + 1 costs `walking[i]`,
+    /// starting simultaneously from all the `(node, initial_cost)` pairs in `sources`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the sources is not a node of the graph.
+    pub fn with_walking_costs_and_sources(walking: Vec<usize>, sources: &[(usize, usize)]) -> Self {
+        Self::with_topology(Line::with_walking_costs(walking), sources)
+    }
+
+    /// Create a new LinearBfs engine over `line`, starting simultaneously from all the
+    /// `(node, initial_cost)` pairs in `sources`.
+    ///
+    /// This is the same as [`with_topology`](Bfs::with_topology).
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the sources is not a node of the line.
+    pub fn with_line(line: Line, sources: &[(usize, usize)]) -> Self {
+        Self::with_topology(line, sources)
+    }
+}
+
+impl<T: Topology> Bfs<T> {
+    /// Create a new Bfs engine over `topology`, starting simultaneously from all the
+    /// `(node, initial_cost)` pairs in `sources`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the sources is not a node of the graph.
+    pub fn with_topology(topology: T, sources: &[(usize, T::Cost)]) -> Self {
+        let mut engine = Bfs {
+            labels: Labels::new(topology.len()),
+            topology,
+            queue: VecDeque::new(),
+            sources: Vec::new(),
+            current: T::Cost::ZERO,
+        };
+
+        engine.seed(sources);
+        engine
+    }
+
+    fn seed(&mut self, sources: &[(usize, T::Cost)]) {
+        for &(position, cost) in sources {
+            if self.labels.improve(cost, position, None) {
+                self.sources.push(State { cost, position });
+            }
+        }
+
+        // NB: State is ordered by decreasing cost, which is what we want here.
+        self.sources.sort_unstable();
+    }
+
+    // Queue a newly improved path, which costs as much as the last examined path or one more.
+    fn enqueue(queue: &mut VecDeque<State<T::Cost>>, current: T::Cost, state: State<T::Cost>) {
+        if state.cost == current {
+            queue.push_front(state);
+        } else {
+            queue.push_back(state);
+        }
+    }
+}
+
+impl<T: Topology> Engine for Bfs<T> {
+    type Cost = T::Cost;
+    type Topology = T;
+
+    fn push(&mut self, cost: T::Cost, step: Step) {
+        debug_assert!(
+            cost == self.current || Some(cost) == self.current.checked_add(T::Cost::ONE),
+            "Bfs only supports costs of 0 or 1"
+        );
+
+        if self.labels.improve(cost, step.to, Some(step)) {
+            let state = State {
+                cost,
+                position: step.to,
+            };
+            Self::enqueue(&mut self.queue, self.current, state);
+        }
+    }
+
+    fn pop(&mut self) -> Option<State<T::Cost>> {
+        loop {
+            // Take the cheapest of the next queued path and the next source.
+            let state = match (self.queue.front(), self.sources.last()) {
+                (Some(queued), Some(source)) if source.cost < queued.cost => self.sources.pop(),
+                (Some(_), _) => self.queue.pop_front(),
+                (None, _) => self.sources.pop(),
+            }?;
+
+            // If we have already found a shorter path to that node, we can safely skip this one.
+            if self.labels.is_stale(state) {
+                continue;
+            }
+
+            self.current = state.cost;
+            for (step, walk) in self.topology.neighbours(state.position) {
+                // Fractional costs such as 0.5 are smaller than 1 too, but not supported either.
+                assert!(
+                    walk == T::Cost::ZERO || walk == T::Cost::ONE,
+                    "Bfs only supports walking costs of 0 or 1"
+                );
+
+                let Some(cost) = self.labels.extend(state.cost, walk, step.to) else {
+                    continue;
+                };
+                if self.labels.improve(cost, step.to, Some(step)) {
+                    let next = State {
+                        cost,
+                        position: step.to,
+                    };
+                    Self::enqueue(&mut self.queue, self.current, next);
+                }
+            }
+
+            return Some(state);
+        }
+    }
+
+    fn reset(&mut self, sources: &[(usize, T::Cost)]) {
+        self.labels.reset();
+        self.queue.clear();
+        self.sources.clear();
+        self.current = T::Cost::ZERO;
+        self.seed(sources);
+    }
+
+    fn reset_with_topology(&mut self, topology: T, sources: &[(usize, T::Cost)]) {
+        self.labels.replace(topology.len());
+        self.topology = topology;
+        self.reset(sources);
+    }
+
+    fn distances(&self) -> &[Option<T::Cost>] {
+        &self.labels.distances
+    }
+
+    fn predecessor(&self, node: usize) -> Option<Step> {
+        self.labels.predecessors[node]
+    }
+
+    fn path_to(&self, node: usize) -> Option<Vec<Step>> {
+        self.labels.path_to(node)
+    }
+
+    fn path_counts(&self) -> &[usize] {
+        &self.labels.counts
+    }
+
+    fn set_path_count_modulus(&mut self, modulus: usize) {
+        self.labels.set_modulus(modulus);
+    }
+}
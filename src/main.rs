@@ -1,150 +1,534 @@
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
-use std::io;
+mod cli;
 
-// A State represents a potential path from node 0 to the node position with cost cost, where the
-// cost represents the energy.
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct State {
-    cost: usize,
-    position: usize,
+use std::any::Any;
+use std::env;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::process;
+use std::time::{Duration, Instant};
+
+use cli::{Args, Command, Queue};
+use symmetrical_palm_tree::generate::{self, Distribution};
+use symmetrical_palm_tree::input::{ParseError, Reader, Shortcuts};
+use symmetrical_palm_tree::output::Writer;
+use symmetrical_palm_tree::queue::{BucketQueue, RadixHeap};
+use symmetrical_palm_tree::random::Rng;
+use symmetrical_palm_tree::{Engine, Line, LinearBfs, LinearDijkstra, State, Step};
+
+// Report an error and exit with `status`, after writing out the results of the previous instances
+// that are still buffered in `out`.
+fn fail(out: &mut impl Write, status: i32, message: impl Display) -> ! {
+    // The output is incomplete anyway, so failing to write it out is not worth reporting.
+    let _ = out.flush();
+    eprintln!("error: {}", message);
+    process::exit(status);
+}
+
+// Report a parse error and exit.
+fn parsed<T>(out: &mut impl Write, result: Result<T, ParseError>) -> T {
+    result.unwrap_or_else(|err| fail(out, 1, err))
+}
+
+// Report a parse error in the candidate answer read from `path` and exit.
+fn answered<T>(out: &mut impl Write, path: &Path, result: Result<T, ParseError>) -> T {
+    result.unwrap_or_else(|err| fail(out, 1, format_args!("{}: {}", path.display(), err)))
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // NB: we want a min-heap, not a max-heap, so we need to flip the `cost` order.
-        other
-            .cost
-            .cmp(&self.cost)
-            .then_with(|| self.position.cmp(&other.position))
+// Report an output error and exit.
+fn written(result: io::Result<()>) {
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        process::exit(1);
     }
 }
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+// Open the file at `path`, exiting if it cannot be opened.
+fn open(path: &Path) -> BufReader<File> {
+    match File::open(path) {
+        Ok(file) => BufReader::new(file),
+        Err(err) => {
+            eprintln!("error: {}: {}", path.display(), err);
+            process::exit(1);
+        }
     }
 }
 
-// This is a helper struct that allows to compute Dijkstra's shortest-path on a graph over a
-// bi-directional line.
-//
-// Conceptually, the graph is pre-populated with the elements 0 .. n with bi-directional edges from
-// i to i + 1 and i - 1.
-//
-// The starting point of the graph is always node 0.
-//
-// Additional edges can be added by the user and are handled through a combination of the pop and
-// push methods.
-//
-// Calling pop() advances the search, and returns the a potential path to examine. The user should
-// apply push any possible transition to the corresponding node, which will be added to the solver
-// if they improve its current cost.
-//
-// Once pop() returns None, the solver has examined all potential paths from the start position to
-// any other position:
-struct LinearDijkstra {
-    // The dist vector maps each 0-indexed node to the current shortest distance to that node. We
-    // know that there is at least a path of length i to node i by walking in a straight line.
-    distances: Vec<usize>,
-
-    // The heap is used to implement a priority queue, so that we always investigate short paths
-    // before long paths (that could end up being discarded).
-    heap: BinaryHeap<State>,
-}
-
-impl LinearDijkstra {
-    // Create a new LinearDijkstra solver with n nodes representings the integers 0 to n - 1.
-    fn new(n: usize) -> Self {
-        let mut heap = BinaryHeap::new();
-        let mut distances = (0..n).map(|_| usize::MAX).collect::<Vec<_>>();
-
-        // We start on node 0 with 0 cost.
-        distances[0] = 0;
-        heap.push(State {
-            cost: 0,
-            position: 0,
-        });
+// The message of the panic raised when a route to a node that has not been reached yet costs more
+// than a usize can hold: by the engines when walking, and by `expand` when taking a shortcut.
+const OVERFLOW: &str = "path cost overflow";
+
+// Whether a panic with `payload` is an overflow, which is an error in the input rather than a bug.
+fn overflowed(payload: &(dyn Any + Send)) -> bool {
+    payload.downcast_ref::<&str>() == Some(&OVERFLOW)
+}
+
+// Run the searches of `f`, reporting an overflow as an error and exiting.
+fn searched<W: Write, T>(writer: &mut Writer<W>, f: impl FnOnce(&mut Writer<W>) -> T) -> T {
+    match panic::catch_unwind(AssertUnwindSafe(|| f(writer))) {
+        Ok(value) => value,
+        Err(payload) if overflowed(&*payload) => {
+            fail(writer.get_mut(), 1, "the cost of a route overflows")
+        }
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+// Convert a 1-indexed node from the command line into a 0-indexed node.
+fn node(out: &mut impl Write, option: &str, node: usize, n: usize) -> usize {
+    if node == 0 || node > n {
+        fail(
+            out,
+            2,
+            format_args!("{} {} is not between 1 and {}", option, node, n),
+        );
+    }
 
-        LinearDijkstra { distances, heap }
+    node - 1
+}
+
+fn main() {
+    let args = Args::parse(env::args().skip(1));
+    if let Command::Generate {
+        seed,
+        size,
+        distribution,
+    } = args.command
+    {
+        generate(seed, size, distribution);
+        return;
     }
 
-    // Discover a new potential path.
-    //
-    // The new potential path is only considered if it has lowest total cost than any current path
-    // to the node.
-    fn push(&mut self, state: State) {
-        if state.cost < self.distances[state.position] {
-            self.distances[state.position] = state.cost;
-            self.heap.push(state);
+    // Overflows are reported by `searched`, without the message of the default hook.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if !overflowed(info.payload()) {
+            hook(info);
         }
+    }));
+
+    // Parsing. Shortcuts are converted to 0-indexed nodes by the parser.
+    let input: Box<dyn BufRead> = match &args.input {
+        Some(path) => Box::new(open(path)),
+        None => Box::new(io::stdin().lock()),
+    };
+    let mut reader = Reader::new(input).rings(args.ring);
+
+    // Errors exit through `fail`, which writes out the results of the previous instances first.
+    let mut writer = Writer::new(BufWriter::new(io::stdout().lock()), args.output)
+        .predecessors(args.predecessors)
+        .path_counts(args.paths)
+        .cases(args.cases)
+        .reachable_only(args.reachable_only);
+    if let Some(marker) = &args.unreachable {
+        writer = writer.unreachable(marker);
+    }
+    let count = if args.cases {
+        parsed(writer.get_mut(), reader.case_count())
+    } else {
+        1
+    };
+
+    // The candidate answers to validate, one per instance.
+    let mut answers = match &args.command {
+        Command::Validate { answer } => Some((answer, Reader::new(open(answer)))),
+        _ => None,
+    };
+
+    // Anything after the last instance, or after its queries, is a mistake in the input such as a
+    // shortcut too many, which is reported before solving anything.
+    if count == 0 {
+        parsed(writer.get_mut(), reader.end());
     }
 
-    // Examine a potential path that could lead to an improvement.
-    //
-    // If appropriate, the neighbours (position - 1 and position + 1) of the new potential path
-    // will automatically be added to the solver. The user should then add any additional shortcuts
-    // that are available before calling pop again.
-    fn pop(&mut self) -> Option<State> {
-        while let Some(State { cost, position }) = self.heap.pop() {
-            // If we have already found a shorter path to that node, we can safely skip this one.
-            if cost > self.distances[position] {
+    let mut engines = Engines::default();
+    for case in 0..count {
+        let instance = parsed(writer.get_mut(), reader.instance(args.format));
+        let n = instance.len();
+        let mut queries = match args.command {
+            Command::Query => Some(parsed(writer.get_mut(), reader.queries(n))),
+            _ => None,
+        };
+        if case + 1 == count {
+            parsed(writer.get_mut(), reader.end());
+        }
+
+        // When all costs are 0 or 1, a BFS finds the same distances faster than Dijkstra's
+        // algorithm, unless a specific queue was requested.
+        let max_cost = instance.max_cost();
+        let bfs = max_cost <= 1;
+        let line = instance.line();
+        let shortcuts = instance.shortcuts;
+
+        let mut sources = args
+            .sources
+            .iter()
+            .map(|&(option, s, cost)| (node(writer.get_mut(), option, s, n), cost))
+            .collect::<Vec<_>>();
+        if sources.is_empty() {
+            sources.push((0, 0));
+        }
+
+        let dial = buckets_fit(max_cost, &sources);
+        if matches!(args.queue, Some(Queue::Dial)) && !dial {
+            fail(
+                writer.get_mut(),
+                1,
+                format_args!(
+                    "--queue dial needs a bucket per cost, and the costs span more than {}",
+                    MAX_BUCKET_SPAN
+                ),
+            );
+        }
+
+        let mode = match args.command {
+            Command::Solve => Mode::Solve {
+                sources,
+                target: None,
+                modulus: args.modulus,
+                summary: args.summary,
+            },
+            Command::Path { target } => Mode::Solve {
+                sources,
+                target: Some(node(writer.get_mut(), "path", target, n)),
+                modulus: args.modulus,
+                summary: false,
+            },
+            Command::Query => Mode::Queries(queries.take().unwrap()),
+            Command::Validate { .. } => {
+                let (path, answers) = answers.as_mut().unwrap();
+                Mode::Validate {
+                    sources,
+                    candidate: answered(writer.get_mut(), path, answers.distances(n)),
+                }
+            }
+            Command::Bench { runs } => {
+                searched(&mut writer, |_| {
+                    bench(&mut engines, line, &shortcuts, &sources, bfs, dial, runs)
+                });
                 continue;
             }
+            Command::Generate { .. } => unreachable!("instances are generated without input"),
+        };
 
-            // We can move forward if we are not at the end
-            if position + 1 < self.distances.len() {
-                self.push(State {
-                    cost: cost + 1,
-                    position: position + 1,
-                });
+        searched(&mut writer, |writer| match args.queue {
+            None if bfs => run(
+                reuse(&mut engines.bfs, line, || LinearBfs::new(1)),
+                &shortcuts,
+                &mode,
+                writer,
+            ),
+            None | Some(Queue::Binary) => run(
+                reuse(&mut engines.binary, line, || LinearDijkstra::new(1)),
+                &shortcuts,
+                &mode,
+                writer,
+            ),
+            Some(Queue::Dial) => run(
+                reuse(&mut engines.dial, line, || {
+                    LinearDijkstra::with_queue(BucketQueue::new(), Vec::new(), &[])
+                }),
+                &shortcuts,
+                &mode,
+                writer,
+            ),
+            Some(Queue::Radix) => run(
+                reuse(&mut engines.radix, line, || {
+                    LinearDijkstra::with_queue(RadixHeap::new(), Vec::new(), &[])
+                }),
+                &shortcuts,
+                &mode,
+                writer,
+            ),
+        });
+    }
+
+    if let Some((path, mut answers)) = answers {
+        answered(writer.get_mut(), path, answers.end());
+    }
+    written(writer.into_inner().map(drop));
+}
+
+// The largest span of costs that a bucket queue is used for. It needs a bucket for each cost in
+// the span, which would take too much memory beyond that.
+const MAX_BUCKET_SPAN: usize = 1 << 20;
+
+// Whether a bucket queue can hold the paths of a search from `sources` with edges costing up to
+// `max_cost`. The queued costs are never further apart than the cost of an edge, except for the
+// initial costs of the sources, which can be anywhere.
+fn buckets_fit(max_cost: usize, sources: &[(usize, usize)]) -> bool {
+    let lowest = sources.iter().map(|&(_, cost)| cost).min().unwrap_or(0);
+    let highest = sources.iter().map(|&(_, cost)| cost).max().unwrap_or(0);
+    max_cost
+        .checked_add(highest - lowest)
+        .is_some_and(|span| span <= MAX_BUCKET_SPAN)
+}
+
+// The engines used so far, kept around so that later instances can reuse their buffers.
+#[derive(Default)]
+struct Engines {
+    bfs: Option<LinearBfs>,
+    binary: Option<LinearDijkstra>,
+    dial: Option<LinearDijkstra<BucketQueue>>,
+    radix: Option<LinearDijkstra<RadixHeap>>,
+}
+
+// Get the engine in `slot` ready to search `line`, creating it with `make` the first time.
+fn reuse<E: Engine<Topology = Line>>(
+    slot: &mut Option<E>,
+    line: Line,
+    make: impl FnOnce() -> E,
+) -> &mut E {
+    let engine = slot.get_or_insert_with(make);
+    engine.reset_with_topology(line, &[]);
+    engine
+}
+
+// What to compute once the instance is parsed.
+enum Mode {
+    // The distances to every node, or the route to the target if there is one. Routes are counted
+    // modulo `modulus`, and the number of unreachable nodes is reported if `summary` is set.
+    Solve {
+        sources: Vec<(usize, usize)>,
+        target: Option<usize>,
+        modulus: usize,
+        summary: bool,
+    },
+    // The distance for each `(s, t)` query.
+    Queries(Vec<(usize, usize)>),
+    // Whether `candidate` holds the distances to every node.
+    Validate {
+        sources: Vec<(usize, usize)>,
+        candidate: Vec<Option<usize>>,
+    },
+}
+
+fn run<E: Engine<Cost = usize>>(
+    solver: &mut E,
+    shortcuts: &Shortcuts,
+    mode: &Mode,
+    writer: &mut Writer<impl Write>,
+) {
+    match mode {
+        Mode::Solve {
+            sources,
+            target,
+            modulus,
+            summary,
+        } => {
+            solver.set_path_count_modulus(*modulus);
+            solve(solver, shortcuts, sources, *target, writer);
+            if *summary {
+                summarize(solver);
             }
+        }
+        Mode::Queries(queries) => answer(solver, shortcuts, queries, writer.get_mut()),
+        Mode::Validate { sources, candidate } => {
+            validate(solver, shortcuts, sources, candidate, writer.get_mut())
+        }
+    }
+}
 
-            // We can move backward if we are not at the start
-            if position > 0 {
-                self.push(State {
-                    cost: cost + 1,
-                    position: position - 1,
-                });
+// Push the shortcuts leaving the node of `state`.
+fn expand<E: Engine<Cost = usize>>(
+    solver: &mut E,
+    shortcuts: &Shortcuts,
+    State { cost, position }: State,
+) {
+    for shortcut in &shortcuts[position] {
+        // Like a walk that overflows, such a shortcut can be dropped if its target already has a
+        // distance, since it cannot improve on it.
+        let Some(cost) = cost.checked_add(shortcut.cost) else {
+            if solver.distances()[shortcut.to].is_none() {
+                panic::panic_any(OVERFLOW);
             }
+            continue;
+        };
+        solver.push(cost, Step::shortcut(position, shortcut.to));
+    }
+}
+
+// Compute the distances from `sources` to every node.
+fn search<E: Engine<Cost = usize>>(
+    solver: &mut E,
+    shortcuts: &Shortcuts,
+    sources: &[(usize, usize)],
+) {
+    // We add in the shortcut paths every time a node is examined.
+    solver.reset(sources);
+    while let Some(state) = solver.pop() {
+        expand(solver, shortcuts, state);
+    }
+}
+
+// Answer each `(s, t)` query with the distance from s to t.
+//
+// We reuse the same solver for all queries, stopping each search as soon as the target is reached.
+fn answer<E: Engine<Cost = usize>>(
+    solver: &mut E,
+    shortcuts: &Shortcuts,
+    queries: &[(usize, usize)],
+    out: &mut impl Write,
+) {
+    for &(s, t) in queries {
+        solver.reset(&[(s, 0)]);
+        let distance = solver.shortest_to(t, |solver, state| expand(solver, shortcuts, state));
 
-            return Some(State { cost, position });
+        // Targets behind one-way or closed segments can be unreachable.
+        match distance {
+            Some(distance) => written(writeln!(out, "{}", distance)),
+            None => written(writeln!(out, "-1")),
         }
+    }
+}
 
-        None
+// Print the distances to every node, or the route to `target` if there is one.
+fn solve<E: Engine<Cost = usize>>(
+    solver: &mut E,
+    shortcuts: &Shortcuts,
+    sources: &[(usize, usize)],
+    target: Option<usize>,
+    writer: &mut Writer<impl Write>,
+) {
+    // Once the search is over, we have examined all possible paths: we just have to print the
+    // output.
+    search(solver, shortcuts, sources);
+    if let Some(target) = target {
+        let Some(path) = solver.path_to(target) else {
+            let message = format!("node {} is not reachable", target + 1);
+            fail(writer.get_mut(), 1, message);
+        };
+
+        route(&path, writer.get_mut());
+        return;
     }
+
+    written(writer.write(solver));
 }
 
-fn main() {
-    // Parsing. Note that we subtract 1 from the shortcut index because that works better with
-    // 0-indexed arrays.
-    let mut lines = io::stdin().lines().map(|l| l.unwrap());
-    let n = lines.next().unwrap().parse::<usize>().unwrap();
-    let shortcuts = lines.next().unwrap();
-    let shortcuts = shortcuts
-        .split(' ')
-        .map(|s| s.parse::<usize>().unwrap() - 1)
-        .collect::<Vec<_>>();
-
-    // Solving.
-    //
-    // We use a LinearDijkstra solver and add in the shortcut paths.
-    let mut solver = LinearDijkstra::new(n);
-    while let Some(State { cost, position }) = solver.pop() {
-        solver.push(State {
-            cost: cost + 1,
-            position: shortcuts[position],
+// Check that `candidate` holds the distances from `sources` to every node, and print `ok` if it
+// does. Otherwise, print the first node where it does not with the route to that node, and exit.
+fn validate<E: Engine<Cost = usize>>(
+    solver: &mut E,
+    shortcuts: &Shortcuts,
+    sources: &[(usize, usize)],
+    candidate: &[Option<usize>],
+    out: &mut impl Write,
+) {
+    search(solver, shortcuts, sources);
+    let mismatch = solver
+        .distances()
+        .iter()
+        .zip(candidate)
+        .position(|(distance, candidate)| distance != candidate);
+    let Some(node) = mismatch else {
+        written(writeln!(out, "ok"));
+        return;
+    };
+
+    // Unreachable nodes are written as -1, like in the plain output.
+    let text = |distance: Option<usize>| distance.map_or("-1".to_string(), |d| d.to_string());
+    written(writeln!(
+        out,
+        "wrong answer for node {}: expected {}, found {}",
+        node + 1,
+        text(solver.distances()[node]),
+        text(candidate[node])
+    ));
+    if let Some(path) = solver.path_to(node) {
+        route(&path, out);
+    }
+
+    written(out.flush());
+    process::exit(1);
+}
+
+// Print each step of `path`, with 1-indexed nodes.
+fn route(path: &[Step], out: &mut impl Write) {
+    for step in path {
+        written(writeln!(
+            out,
+            "{} -> {} ({})",
+            step.from + 1,
+            step.to + 1,
+            step.kind
+        ));
+    }
+}
+
+// Print the number of nodes that the last search could not reach to stderr, so that it does not
+// get mixed up with the distances.
+fn summarize(solver: &impl Engine) {
+    let distances = solver.distances();
+    let unreachable = distances.iter().filter(|d| d.is_none()).count();
+    eprintln!(
+        "{} of {} nodes are unreachable",
+        unreachable,
+        distances.len()
+    );
+}
+
+// Time a full search with each engine, and print the average time per run. The BFS is only timed
+// when all costs are 0 or 1, and the bucket queue when its buckets fit in memory.
+fn bench(
+    engines: &mut Engines,
+    line: Line,
+    shortcuts: &Shortcuts,
+    sources: &[(usize, usize)],
+    bfs: bool,
+    dial: bool,
+    runs: u32,
+) {
+    fn time<E: Engine<Cost = usize>>(
+        solver: &mut E,
+        shortcuts: &Shortcuts,
+        sources: &[(usize, usize)],
+        runs: u32,
+    ) -> Duration {
+        let start = Instant::now();
+        for _ in 0..runs {
+            search(solver, shortcuts, sources);
+        }
+        start.elapsed() / runs
+    }
+
+    let binary = reuse(&mut engines.binary, line.clone(), || LinearDijkstra::new(1));
+    print!("binary {:>9.2?}", time(binary, shortcuts, sources, runs));
+
+    if dial {
+        let dial = reuse(&mut engines.dial, line.clone(), || {
+            LinearDijkstra::with_queue(BucketQueue::new(), Vec::new(), &[])
         });
+        print!("  dial {:>9.2?}", time(dial, shortcuts, sources, runs));
+    } else {
+        print!("  dial {:>9}", "-");
     }
 
-    // Once pop() returns None, we have examined all possible paths: we just have to print the
-    // output.
-    //
-    // Note that going to the first position always has a cost of 0, which we use to intersperse
-    // the spaces.
-    print!("0");
-    for n in solver.distances.iter().skip(1) {
-        print!(" {}", n);
-    }
-    println!("");
+    let radix = reuse(&mut engines.radix, line.clone(), || {
+        LinearDijkstra::with_queue(RadixHeap::new(), Vec::new(), &[])
+    });
+    print!("  radix {:>9.2?}", time(radix, shortcuts, sources, runs));
+
+    if bfs {
+        let bfs = reuse(&mut engines.bfs, line, || LinearBfs::new(1));
+        print!("  bfs {:>9.2?}", time(bfs, shortcuts, sources, runs));
+    }
+
+    println!();
+}
+
+// Print a random instance with `size` nodes in the classic format.
+fn generate(seed: u64, size: usize, distribution: Distribution) {
+    let shortcuts = generate::shortcuts(size, distribution, &mut Rng::new(seed));
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    written(writeln!(out, "{}", size));
+    for (i, to) in shortcuts.iter().enumerate() {
+        let separator = if i == 0 { "" } else { " " };
+        written(write!(out, "{}{}", separator, to + 1));
+    }
+    written(writeln!(out));
+    written(out.flush());
 }
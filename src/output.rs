@@ -0,0 +1,223 @@
+//! Writing out the distances computed by an [`Engine`].
+//!
+//! Distances can be written in one of the following [`Format`]s. In all cases, nodes are 1-indexed.
+//!
+//! - The plain format is a single line with the space-separated distances to each node.
+//!
+//! - The JSON format is a single line with an array of records, one per node, such as
+//!   `[{"node":1,"distance":0},{"node":2,"distance":1}]`.
+//!
+//! - The CSV format has a header line, followed by one `node,distance` line per node.
+//!
+//! The JSON and CSV records can also include the predecessor of each node on its route, which is
+//! `null` (JSON) or empty (CSV) for sources, and the number of shortest routes to each node in a
+//! `paths` field. When several instances are written in CSV, each line
+//! starts with the (1-indexed) number of its instance, in a `case` column.
+//!
+//! Nodes that cannot be reached have a distance of `-1` in the plain and CSV formats, which can be
+//! replaced by another marker such as `inf`, and no `distance` field at all in JSON. They can also
+//! be left out entirely, in which case the plain format lists `node:distance` pairs instead, such
+//! as `1:0 3:2`, since the position of each distance no longer gives its node.
+
+use std::io::{self, Write};
+
+use crate::cost::Cost;
+use crate::Engine;
+
+/// The supported output formats. See the [module documentation](self) for details.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Format {
+    /// Space-separated distances.
+    #[default]
+    Plain,
+    /// An array of JSON records.
+    Json,
+    /// Comma-separated records with a header.
+    Csv,
+}
+
+/// Writes the distances of successive instances to an output.
+#[derive(Debug)]
+pub struct Writer<W> {
+    out: W,
+    format: Format,
+    predecessors: bool,
+    path_counts: bool,
+    cases: bool,
+    unreachable: String,
+    reachable_only: bool,
+
+    // The number of instances written so far.
+    written: usize,
+}
+
+impl<W: Write> Writer<W> {
+    /// Create a writer to `out` in the given `format`.
+    pub fn new(out: W, format: Format) -> Self {
+        Writer {
+            out,
+            format,
+            predecessors: false,
+            path_counts: false,
+            cases: false,
+            unreachable: "-1".to_string(),
+            reachable_only: false,
+            written: 0,
+        }
+    }
+
+    /// Whether to include the predecessor of each node in the records. This has no effect on the
+    /// plain format.
+    pub fn predecessors(mut self, predecessors: bool) -> Self {
+        self.predecessors = predecessors;
+        self
+    }
+
+    /// Whether to include the number of shortest routes to each node in the records, as computed by
+    /// [`Engine::path_counts`]. This has no effect on the plain format.
+    pub fn path_counts(mut self, path_counts: bool) -> Self {
+        self.path_counts = path_counts;
+        self
+    }
+
+    /// Whether to number the instances in a `case` column. This only affects the CSV format.
+    pub fn cases(mut self, cases: bool) -> Self {
+        self.cases = cases;
+        self
+    }
+
+    /// The distance to write for unreachable nodes in the plain and CSV formats, instead of `-1`.
+    pub fn unreachable(mut self, marker: &str) -> Self {
+        self.unreachable = marker.to_string();
+        self
+    }
+
+    /// Whether to leave out the nodes that cannot be reached.
+    pub fn reachable_only(mut self, reachable_only: bool) -> Self {
+        self.reachable_only = reachable_only;
+        self
+    }
+
+    /// Write the current distances of `engine`, normally once its search is over.
+    pub fn write(&mut self, engine: &impl Engine) -> io::Result<()> {
+        match self.format {
+            Format::Plain => self.plain(engine)?,
+            Format::Json => self.json(engine)?,
+            Format::Csv => self.csv(engine)?,
+        }
+
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Get a mutable reference to the output, to write anything else than distances to it.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.out
+    }
+
+    /// Flush the output and return it.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    // The 1-indexed predecessor of `node`, if it has one.
+    fn predecessor(engine: &impl Engine, node: usize) -> Option<usize> {
+        engine.predecessor(node).map(|step| step.from + 1)
+    }
+
+    // The nodes to write, with their distances.
+    fn nodes<'a, C: Cost>(
+        &self,
+        distances: &'a [Option<C>],
+    ) -> impl Iterator<Item = (usize, Option<C>)> + 'a {
+        let reachable_only = self.reachable_only;
+        distances
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(move |(_, distance)| !reachable_only || distance.is_some())
+    }
+
+    // Write `distance`, or the unreachable marker if there is none.
+    fn distance(&mut self, distance: Option<impl Cost>) -> io::Result<()> {
+        match distance {
+            Some(distance) => write!(self.out, "{}", distance),
+            None => write!(self.out, "{}", self.unreachable),
+        }
+    }
+
+    fn plain(&mut self, engine: &impl Engine) -> io::Result<()> {
+        for (i, (node, distance)) in self.nodes(engine.distances()).enumerate() {
+            if i > 0 {
+                write!(self.out, " ")?;
+            }
+            if self.reachable_only {
+                write!(self.out, "{}:", node + 1)?;
+            }
+            self.distance(distance)?;
+        }
+        writeln!(self.out)
+    }
+
+    fn json(&mut self, engine: &impl Engine) -> io::Result<()> {
+        write!(self.out, "[")?;
+        for (i, (node, distance)) in self.nodes(engine.distances()).enumerate() {
+            if i > 0 {
+                write!(self.out, ",")?;
+            }
+
+            write!(self.out, "{{\"node\":{}", node + 1)?;
+            if let Some(distance) = distance {
+                write!(self.out, ",\"distance\":{}", distance)?;
+            }
+            if self.predecessors {
+                match Self::predecessor(engine, node) {
+                    Some(predecessor) => write!(self.out, ",\"predecessor\":{}", predecessor)?,
+                    None => write!(self.out, ",\"predecessor\":null")?,
+                }
+            }
+            if self.path_counts {
+                write!(self.out, ",\"paths\":{}", engine.path_counts()[node])?;
+            }
+            write!(self.out, "}}")?;
+        }
+        writeln!(self.out, "]")
+    }
+
+    fn csv(&mut self, engine: &impl Engine) -> io::Result<()> {
+        if self.written == 0 {
+            if self.cases {
+                write!(self.out, "case,")?;
+            }
+            write!(self.out, "node,distance")?;
+            if self.predecessors {
+                write!(self.out, ",predecessor")?;
+            }
+            if self.path_counts {
+                write!(self.out, ",paths")?;
+            }
+            writeln!(self.out)?;
+        }
+
+        for (node, distance) in self.nodes(engine.distances()) {
+            if self.cases {
+                write!(self.out, "{},", self.written + 1)?;
+            }
+            write!(self.out, "{},", node + 1)?;
+            self.distance(distance)?;
+            if self.predecessors {
+                write!(self.out, ",")?;
+                if let Some(predecessor) = Self::predecessor(engine, node) {
+                    write!(self.out, "{}", predecessor)?;
+                }
+            }
+            if self.path_counts {
+                write!(self.out, ",{}", engine.path_counts()[node])?;
+            }
+            writeln!(self.out)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+//! Priority queues for [`LinearDijkstra`](crate::LinearDijkstra).
+//!
+//! The solver only ever needs to insert potential paths and to remove the one with the lowest cost,
+//! which is captured by the [`PriorityQueue`] trait. Besides the standard [`BinaryHeap`], which
+//! works for any costs, this module provides two queues that take advantage of the fact that costs
+//! are integers and that Dijkstra's algorithm never inserts a path cheaper than the last one it
+//! removed:
+//!
+//! - [`BucketQueue`] is Dial's algorithm: one bucket per cost, which is fastest when the costs of
+//!   the queued paths span a small range, that is when edge costs are small.
+//! - [`RadixHeap`] groups costs by the highest bit in which they differ from the last removed cost,
+//!   and works well for any costs.
+//!
+//! The integer queues require [`Unsigned`] costs, while the [`BinaryHeap`] accepts any [`Cost`].
+
+use std::collections::{BinaryHeap, VecDeque};
+
+use crate::cost::{Cost, Unsigned};
+use crate::State;
+
+/// A queue of potential paths, returning the cheapest one first.
+///
+/// Queues are allowed to assume that they are used by Dijkstra's algorithm, that is that no state
+/// is pushed with a lower cost than the last one popped since the last [`clear`](Self::clear).
+pub trait PriorityQueue<C = usize> {
+    /// Add `state` to the queue.
+    fn push(&mut self, state: State<C>);
+
+    /// Remove the state with the lowest cost from the queue, or return `None` if it is empty.
+    ///
+    /// Ties between states with the same cost can be broken arbitrarily.
+    fn pop(&mut self) -> Option<State<C>>;
+
+    /// Remove all states from the queue, keeping its allocations for reuse.
+    fn clear(&mut self);
+}
+
+impl<C: Cost> PriorityQueue<C> for BinaryHeap<State<C>> {
+    fn push(&mut self, state: State<C>) {
+        BinaryHeap::push(self, state)
+    }
+
+    fn pop(&mut self) -> Option<State<C>> {
+        BinaryHeap::pop(self)
+    }
+
+    fn clear(&mut self) {
+        BinaryHeap::clear(self)
+    }
+}
+
+/// Dial's bucket queue, with one bucket for each cost between the lowest and highest queued costs.
+///
+/// Both operations take constant time, plus the time needed to skip over empty buckets when
+/// popping. This makes it the fastest queue for small integer costs, but it should not be used
+/// when the costs in the queue can span a large range, as the number of buckets grows with it.
+#[derive(Clone, Debug)]
+pub struct BucketQueue<C = usize> {
+    // buckets[i] holds the nodes queued with cost base + i. Emptied buckets are moved to the back
+    // rather than dropped, so that they are reused as the window of costs moves forward.
+    buckets: VecDeque<Vec<usize>>,
+    base: C,
+    len: usize,
+}
+
+impl<C: Unsigned> BucketQueue<C> {
+    /// Create an empty bucket queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<C: Unsigned> Default for BucketQueue<C> {
+    fn default() -> Self {
+        BucketQueue {
+            buckets: VecDeque::new(),
+            base: C::ZERO,
+            len: 0,
+        }
+    }
+}
+
+impl<C: Unsigned> PriorityQueue<C> for BucketQueue<C> {
+    fn push(&mut self, State { cost, position }: State<C>) {
+        if self.len == 0 {
+            self.base = cost;
+        }
+
+        // Paths cheaper than the base can only happen while seeding sources, before the first pop.
+        if cost < self.base {
+            for _ in 0..self.base.offset_from(cost) {
+                self.buckets.push_front(Vec::new());
+            }
+            self.base = cost;
+        }
+
+        let index = cost.offset_from(self.base);
+        if index >= self.buckets.len() {
+            self.buckets.resize_with(index + 1, Vec::new);
+        }
+
+        self.buckets[index].push(position);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<State<C>> {
+        if self.len == 0 {
+            return None;
+        }
+
+        // There is a non-empty bucket somewhere, so this terminates before the base overflows.
+        loop {
+            if let Some(position) = self.buckets[0].pop() {
+                self.len -= 1;
+                return Some(State {
+                    cost: self.base,
+                    position,
+                });
+            }
+
+            self.buckets.rotate_left(1);
+            self.base = self.base.saturating_add(C::ONE);
+        }
+    }
+
+    fn clear(&mut self) {
+        for bucket in &mut self.buckets {
+            bucket.clear();
+        }
+        self.len = 0;
+    }
+}
+
+/// A radix heap, for integer costs that are popped in increasing order.
+///
+/// States are stored in buckets according to the highest bit in which their cost differs from the
+/// last popped cost. Popping from an empty first bucket redistributes the next non-empty bucket,
+/// and each state can only move down a bucket a limited number of times, so both operations take
+/// amortized `O(log C)` time where `C` is the largest cost.
+#[derive(Clone, Debug)]
+pub struct RadixHeap<C = usize> {
+    buckets: Vec<Vec<State<C>>>,
+    last: C,
+    len: usize,
+}
+
+impl<C: Unsigned> RadixHeap<C> {
+    /// Create an empty radix heap.
+    pub fn new() -> Self {
+        RadixHeap {
+            buckets: vec![Vec::new(); C::BITS as usize + 1],
+            last: C::ZERO,
+            len: 0,
+        }
+    }
+
+    // The bucket for `cost`: 0 if it is equal to the last popped cost, or one more than the index
+    // of the highest bit in which they differ.
+    fn bucket(&self, cost: C) -> usize {
+        cost.highest_differing_bit(self.last) as usize
+    }
+}
+
+impl<C: Unsigned> Default for RadixHeap<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Unsigned> PriorityQueue<C> for RadixHeap<C> {
+    fn push(&mut self, state: State<C>) {
+        debug_assert!(state.cost >= self.last, "RadixHeap costs must not decrease");
+
+        let bucket = self.bucket(state.cost);
+        self.buckets[bucket].push(state);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<State<C>> {
+        if self.len == 0 {
+            return None;
+        }
+
+        if self.buckets[0].is_empty() {
+            // Move the minimum of the first non-empty bucket to the front. All the states of that
+            // bucket then go to lower buckets, since they agree with the new minimum on all the
+            // bits above the one that caused them to land in the same bucket.
+            let index = self.buckets.iter().position(|b| !b.is_empty()).unwrap();
+            let states = std::mem::take(&mut self.buckets[index]);
+            self.last = states.iter().map(|state| state.cost).min().unwrap();
+            for &state in &states {
+                let bucket = self.bucket(state.cost);
+                self.buckets[bucket].push(state);
+            }
+
+            // Give the allocation back to the emptied bucket.
+            self.buckets[index] = states;
+            self.buckets[index].clear();
+        }
+
+        self.len -= 1;
+        self.buckets[0].pop()
+    }
+
+    fn clear(&mut self) {
+        for bucket in &mut self.buckets {
+            bucket.clear();
+        }
+        self.last = C::ZERO;
+        self.len = 0;
+    }
+}
@@ -0,0 +1,69 @@
+//! A small pseudo-random number generator, for randomized tests and generated instances.
+//!
+//! The same seed always gives the same sequence of numbers, on every platform, so that a failing
+//! test or a generated instance can be reproduced from its seed alone.
+
+/// A xorshift64* generator. It is fast and good enough for tests, but not cryptographically secure.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    // Never 0, which is the only state that xorshift cannot leave.
+    state: u64,
+}
+
+impl Rng {
+    /// A generator seeded with `seed`. Any seed is valid, including 0.
+    pub fn new(seed: u64) -> Self {
+        // Scramble the seed with a round of SplitMix64, so that close seeds give unrelated
+        // sequences.
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+
+        Rng { state: z.max(1) }
+    }
+
+    /// The next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// A number between 0 and `bound - 1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound` is 0.
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "cannot pick a number below 0");
+
+        // Multiplying rather than taking a remainder uses the high bits, which are the best ones.
+        // The bias is at most bound / 2^64, which is negligible for our purposes.
+        ((u128::from(self.next_u64()) * bound as u128) >> 64) as usize
+    }
+
+    /// A number between `low` and `high`, both included.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low` is larger than `high`.
+    pub fn between(&mut self, low: usize, high: usize) -> usize {
+        assert!(low <= high, "empty range");
+
+        match (high - low).checked_add(1) {
+            Some(bound) => low + self.below(bound),
+            None => self.next_u64() as usize,
+        }
+    }
+
+    /// `true` with probability `numerator / denominator`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denominator` is 0.
+    pub fn chance(&mut self, numerator: usize, denominator: usize) -> bool {
+        self.below(denominator) < numerator
+    }
+}
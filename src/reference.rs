@@ -0,0 +1,77 @@
+//! A brute-force reference solver, to check the search engines against.
+//!
+//! It builds the whole graph of an [`Instance`] explicitly, walking segments included, and runs
+//! the Bellman-Ford algorithm on it. This takes `O(n · m)` time, so it is only meant for small
+//! instances, but it shares no code with the engines and is simple enough to be obviously correct.
+
+use crate::input::Instance;
+
+/// The edges of the graph of `instance`, as `(from, to, cost)` triples.
+pub fn edges(instance: &Instance) -> Vec<(usize, usize, usize)> {
+    let n = instance.len();
+    let mut edges = Vec::new();
+
+    // Segment i joins node i to the next one, which wraps around to node 0 for the last segment of
+    // a ring.
+    for (i, (&cost, &direction)) in instance
+        .walking
+        .iter()
+        .zip(&instance.directions)
+        .enumerate()
+    {
+        let next = (i + 1) % n;
+        if direction.forward() {
+            edges.push((i, next, cost));
+        }
+        if direction.backward() {
+            edges.push((next, i, cost));
+        }
+    }
+
+    for (from, shortcuts) in instance.shortcuts.iter().enumerate() {
+        for shortcut in shortcuts {
+            edges.push((from, shortcut.to, shortcut.cost));
+        }
+    }
+
+    edges
+}
+
+/// The distances from the `(node, initial_cost)` pairs in `sources` to each node of `instance`,
+/// or `None` for the nodes that cannot be reached.
+///
+/// # Panics
+///
+/// Panics if any of the sources is not a node of the instance.
+pub fn distances(instance: &Instance, sources: &[(usize, usize)]) -> Vec<Option<usize>> {
+    let edges = edges(instance);
+    let mut distances = vec![None; instance.len()];
+    for &(node, cost) in sources {
+        if distances[node].is_none_or(|d| cost < d) {
+            distances[node] = Some(cost);
+        }
+    }
+
+    // Each round finds all shortest paths with one more edge, and shortest paths have at most
+    // n - 1 edges since no cost is negative. We stop early once a round changes nothing.
+    for _ in 1..instance.len() {
+        let mut changed = false;
+        for &(from, to, cost) in &edges {
+            let Some(distance) = distances[from] else {
+                continue;
+            };
+
+            let distance = distance + cost;
+            if distances[to].is_none_or(|d| distance < d) {
+                distances[to] = Some(distance);
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    distances
+}
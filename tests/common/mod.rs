@@ -0,0 +1,99 @@
+// Helpers shared by the integration tests, each of which only uses some of them.
+#![allow(dead_code)]
+
+use std::ops::RangeInclusive;
+
+use symmetrical_palm_tree::cost::Cost;
+use symmetrical_palm_tree::input::{Instance, Shortcut, Shortcuts};
+use symmetrical_palm_tree::random::Rng;
+use symmetrical_palm_tree::{Direction, Engine, Line, Step};
+
+// Every direction a segment can be walked in.
+pub const ALL_DIRECTIONS: [Direction; 4] = [
+    Direction::Both,
+    Direction::Forward,
+    Direction::Backward,
+    Direction::Closed,
+];
+
+// A random instance with up to `nodes` nodes, possibly a ring, with walking and shortcut costs in
+// `costs` and segments that can be walked in any of `directions`. The shortcuts are either one per
+// node like the classic format, or any number of them like the edge list one.
+pub fn instance(
+    rng: &mut Rng,
+    nodes: usize,
+    costs: RangeInclusive<usize>,
+    directions: &[Direction],
+) -> Instance {
+    let n = rng.between(1, nodes);
+    let ring = rng.chance(1, 4);
+    let segments = if ring { n } else { n - 1 };
+
+    let cost = |rng: &mut Rng| rng.between(*costs.start(), *costs.end());
+    let walking = (0..segments).map(|_| cost(rng)).collect();
+    let directions = (0..segments)
+        .map(|_| directions[rng.below(directions.len())])
+        .collect();
+
+    let shortcut = |rng: &mut Rng| Shortcut {
+        to: rng.below(n),
+        cost: cost(rng),
+    };
+    let shortcuts = if rng.chance(1, 2) {
+        Shortcuts::one_per_node((0..n).map(|_| shortcut(rng)).collect())
+    } else {
+        let edges = (0..rng.between(0, 2 * n))
+            .map(|_| (rng.below(n), shortcut(rng)))
+            .collect::<Vec<_>>();
+        Shortcuts::from_edges(n, &edges)
+    };
+
+    Instance {
+        walking,
+        directions,
+        ring,
+        shortcuts,
+    }
+}
+
+// Either node 0 alone, or a few random sources among `n` nodes with initial costs.
+pub fn sources(rng: &mut Rng, n: usize) -> Vec<(usize, usize)> {
+    if rng.chance(1, 2) {
+        return vec![(0, 0)];
+    }
+
+    (0..rng.between(1, 3))
+        .map(|_| (rng.below(n), rng.between(0, 3)))
+        .collect()
+}
+
+// Run a full search with `engine`, pushing the `(to, cost)` shortcuts that `shortcuts` gives for
+// each examined node.
+pub fn search<E, I>(engine: &mut E, mut shortcuts: impl FnMut(usize) -> I)
+where
+    E: Engine,
+    I: IntoIterator<Item = (usize, E::Cost)>,
+{
+    while let Some(state) = engine.pop() {
+        for (to, cost) in shortcuts(state.position) {
+            let cost = state.cost.checked_add(cost).unwrap();
+            engine.push(cost, Step::shortcut(state.position, to));
+        }
+    }
+}
+
+// Run a full search on `instance` with `engine` from `sources`, taking all the shortcuts of each
+// examined node.
+pub fn solve<E: Engine<Cost = usize, Topology = Line>>(
+    engine: &mut E,
+    instance: &Instance,
+    sources: &[(usize, usize)],
+) {
+    engine.reset_with_topology(instance.line(), sources);
+    search(engine, |position| {
+        let shortcuts = &instance.shortcuts[position];
+        shortcuts
+            .iter()
+            .map(|shortcut| (shortcut.to, shortcut.cost))
+    });
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use symmetrical_palm_tree::adjacency::Adjacency;
+use symmetrical_palm_tree::cost::{Cost, Float};
+use symmetrical_palm_tree::queue::{BucketQueue, RadixHeap};
+use symmetrical_palm_tree::{Bfs, Dijkstra, Engine, Line};
+
+#[test]
+fn narrow_integer_costs() {
+    let line = Line::<u8>::with_walking_costs(vec![200, 50]);
+    let mut solver = Dijkstra::with_topology(line, &[(0, 0)]);
+    while solver.pop().is_some() {}
+    assert_eq!(solver.distances(), [Some(0), Some(200), Some(250)]);
+}
+
+#[test]
+fn wide_integer_costs_in_integer_queues() {
+    let big = u128::MAX / 4;
+    let line = Line::with_walking_costs(vec![big, big, 1]);
+
+    let mut radix = Dijkstra::with_queue_and_topology(RadixHeap::new(), line.clone(), &[(0, 0)]);
+    while radix.pop().is_some() {}
+    assert_eq!(
+        radix.distances(),
+        [Some(0), Some(big), Some(2 * big), Some(2 * big + 1)]
+    );
+
+    // Bucket queues only need as many buckets as the span of the queued costs.
+    let mut dial = Dijkstra::with_queue_and_topology(BucketQueue::new(), line, &[(1, big)]);
+    while dial.pop().is_some() {}
+    assert_eq!(
+        dial.distances(),
+        [Some(2 * big), Some(big), Some(2 * big), Some(2 * big + 1)]
+    );
+}
+
+#[test]
+fn float_costs() {
+    let cost = |value| Float::new(value).unwrap();
+    let graph = Adjacency::from_edges(
+        3,
+        &[(0, 1, cost(0.5)), (1, 2, cost(0.25)), (0, 2, cost(1.0))],
+    );
+    let mut solver = Dijkstra::with_topology(graph, &[(0, Float::ZERO)]);
+    common::search(&mut solver, |position| {
+        (position == 2).then_some((0, Float::ZERO))
+    });
+
+    let distances = solver
+        .distances()
+        .iter()
+        .map(|d| d.unwrap().get())
+        .collect::<Vec<_>>();
+    assert_eq!(distances, [0.0, 0.5, 0.75]);
+}
+
+#[test]
+#[should_panic(expected = "Bfs only supports walking costs of 0 or 1")]
+fn bfs_rejects_fractional_costs() {
+    // A BFS would settle node 1 at 1.0, when going through node 2 costs 0.6.
+    let cost = |value| Float::new(value).unwrap();
+    let graph = Adjacency::from_edges(
+        3,
+        &[(0, 1, cost(1.0)), (0, 2, cost(0.5)), (2, 1, cost(0.1))],
+    );
+    let mut bfs = Bfs::with_topology(graph, &[(0, Float::ZERO)]);
+    bfs.shortest_to(1, |_, _| {});
+}
+
+#[test]
+fn invalid_floats_are_rejected() {
+    assert!(Float::new(-1.0).is_none());
+    assert!(Float::new(f64::NAN).is_none());
+    assert!(Float::new(f64::INFINITY).is_none());
+    assert_eq!(Float::new(-0.0), Some(Float::ZERO));
+}
+
+#[test]
+fn unreachable_nodes_have_no_distance() {
+    let graph = Adjacency::from_edges(3, &[(0, 1, 7u32), (2, 0, 1)]);
+    let mut solver = Bfs::with_topology(Line::<u32>::new(1), &[(0, 0)]);
+    solver.reset_with_topology(Line::new(2), &[(1, 0)]);
+    while solver.pop().is_some() {}
+    assert_eq!(solver.distances(), [Some(1), Some(0)]);
+
+    let mut solver = Dijkstra::with_topology(graph, &[(0, 0)]);
+    while solver.pop().is_some() {}
+    assert_eq!(solver.distances(), [Some(0), Some(7), None]);
+    assert_eq!(solver.shortest_to(2, |_, _| {}), None);
+}
+
+#[test]
+#[should_panic(expected = "path cost overflow")]
+fn overflowing_paths_panic() {
+    let line = Line::<u8>::with_walking_costs(vec![200, 100]);
+    let mut solver = Dijkstra::with_topology(line, &[(0, 0)]);
+    while solver.pop().is_some() {}
+}
+
+#[test]
+fn checked_and_saturating_sums() {
+    assert_eq!(Cost::checked_add(250u8, 6), None);
+    assert_eq!(Cost::saturating_add(250u8, 6), u8::MAX);
+    assert_eq!(Cost::checked_add(Float::ONE, Float::ONE), Float::new(2.0));
+    let max = Float::new(f64::MAX).unwrap();
+    assert_eq!(max.checked_add(max), None);
+    assert_eq!(max.saturating_add(max), max);
+}
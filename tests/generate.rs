@@ -0,0 +1,51 @@
+use symmetrical_palm_tree::generate::{self, Distribution};
+use symmetrical_palm_tree::random::Rng;
+
+const DISTRIBUTIONS: [Distribution; 4] = [
+    Distribution::Uniform,
+    Distribution::SelfLoops,
+    Distribution::Chains,
+    Distribution::Churn,
+];
+
+#[test]
+fn shortcuts_stay_on_the_line() {
+    let mut rng = Rng::new(7);
+    for distribution in DISTRIBUTIONS {
+        for n in 1..50 {
+            let shortcuts = generate::shortcuts(n, distribution, &mut rng);
+            assert_eq!(shortcuts.len(), n);
+            assert!(shortcuts.iter().all(|&to| to < n), "{:?}", distribution);
+        }
+    }
+}
+
+#[test]
+fn same_seed_same_instance() {
+    for distribution in DISTRIBUTIONS {
+        let generate = |seed| generate::shortcuts(100, distribution, &mut Rng::new(seed));
+        assert_eq!(generate(1), generate(1));
+    }
+    let uniform = |seed| generate::shortcuts(100, Distribution::Uniform, &mut Rng::new(seed));
+    assert_ne!(uniform(1), uniform(2));
+}
+
+#[test]
+fn fixed_patterns() {
+    let mut rng = Rng::new(0);
+    assert_eq!(
+        generate::shortcuts(5, Distribution::SelfLoops, &mut rng),
+        [0, 1, 2, 3, 4]
+    );
+    assert_eq!(
+        generate::shortcuts(5, Distribution::Churn, &mut rng),
+        [1, 3, 0, 2, 4]
+    );
+
+    // Jumps go at least 2 nodes ahead, except near the end of the line.
+    let chains = generate::shortcuts(64, Distribution::Chains, &mut rng);
+    assert!(chains
+        .iter()
+        .enumerate()
+        .all(|(i, &to)| (i + 8..=i + 16).contains(&to) || to == 63));
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use symmetrical_palm_tree::grid::{Connectivity, Grid};
+use symmetrical_palm_tree::queue::BucketQueue;
+use symmetrical_palm_tree::{GridDijkstra, StepKind};
+
+// Run a full search without any shortcut from the top left cell, and return the distances row by
+// row.
+fn walk(grid: Grid) -> Vec<Vec<Option<usize>>> {
+    let width = grid.width();
+    let mut solver = GridDijkstra::with_topology(grid, &[(0, 0)]);
+    while solver.pop().is_some() {}
+    solver
+        .distances()
+        .chunks(width)
+        .map(<[_]>::to_vec)
+        .collect()
+}
+
+#[test]
+fn four_connected() {
+    assert_eq!(
+        walk(Grid::new(3, 2)),
+        [[Some(0), Some(1), Some(2)], [Some(1), Some(2), Some(3)]]
+    );
+}
+
+#[test]
+fn eight_connected() {
+    let grid = Grid::new(3, 3).with_connectivity(Connectivity::Eight);
+    assert_eq!(
+        walk(grid),
+        [
+            [Some(0), Some(1), Some(2)],
+            [Some(1), Some(1), Some(2)],
+            [Some(2), Some(2), Some(2)]
+        ]
+    );
+}
+
+#[test]
+fn corner_costs() {
+    let grid = Grid::new(3, 3)
+        .with_connectivity(Connectivity::Eight)
+        .with_costs(2, 3);
+    assert_eq!(
+        walk(grid),
+        [
+            [Some(0), Some(2), Some(4)],
+            [Some(2), Some(3), Some(5)],
+            [Some(4), Some(5), Some(6)]
+        ]
+    );
+}
+
+#[test]
+fn obstacles_are_walked_around() {
+    let grid = Grid::new(3, 3).with_obstacles(&[(1, 0), (1, 1)]);
+    assert_eq!(
+        walk(grid),
+        [
+            [Some(0), None, Some(6)],
+            [Some(1), None, Some(5)],
+            [Some(2), Some(3), Some(4)]
+        ]
+    );
+}
+
+#[test]
+fn walls_can_make_cells_unreachable() {
+    let grid = Grid::new(3, 2).with_obstacles(&[(1, 0), (1, 1)]);
+    assert_eq!(walk(grid), [[Some(0), None, None], [Some(1), None, None]]);
+}
+
+#[test]
+fn diagonal_squeezes_between_corners() {
+    let grid = Grid::new(2, 2)
+        .with_connectivity(Connectivity::Eight)
+        .with_obstacles(&[(1, 0), (0, 1)]);
+    assert_eq!(walk(grid), [[Some(0), None], [None, Some(1)]]);
+}
+
+#[test]
+fn shortcuts_from_obstacles() {
+    let grid = Grid::new(3, 1).with_obstacles(&[(1, 0)]);
+    let mut solver = GridDijkstra::with_topology(grid, &[(0, 0)]);
+    common::search(&mut solver, |position| match position {
+        0 => Some((1, 5)),
+        1 => Some((2, 5)),
+        _ => None,
+    });
+    assert_eq!(solver.distances(), [Some(0), Some(5), Some(10)]);
+}
+
+#[test]
+fn paths_and_counts() {
+    let grid = Grid::new(3, 3);
+    let mut solver = GridDijkstra::with_topology(grid, &[(0, 0)]);
+    while solver.pop().is_some() {}
+
+    // There are 4 choose 2 ways to go right twice and down twice.
+    assert_eq!(solver.path_counts()[8], 6);
+
+    let path = solver.path_to(8).unwrap();
+    assert_eq!(path.len(), 4);
+    assert!(path.iter().all(|step| step.kind == StepKind::Walk));
+    assert_eq!(path.last().unwrap().to, 8);
+}
+
+#[test]
+fn reset_with_another_grid() {
+    let mut solver =
+        GridDijkstra::with_queue_and_topology(BucketQueue::new(), Grid::new(2, 2), &[]);
+    solver.reset_with_topology(Grid::new(1, 3), &[(2, 0)]);
+    while solver.pop().is_some() {}
+    assert_eq!(solver.distances(), [Some(2), Some(1), Some(0)]);
+}
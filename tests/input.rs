@@ -0,0 +1,363 @@
+use std::io::BufReader;
+use symmetrical_palm_tree::input::{parse, Format, Instance, ParseError, Reader, Shortcut};
+use symmetrical_palm_tree::Direction;
+
+// The targets of the shortcuts of each node, 1-indexed like the input.
+fn targets(instance: &Instance) -> Vec<Vec<usize>> {
+    instance
+        .shortcuts
+        .iter()
+        .map(|shortcuts| shortcuts.iter().map(|shortcut| shortcut.to + 1).collect())
+        .collect()
+}
+
+fn classic(input: &str) -> Vec<Vec<usize>> {
+    targets(&parse(input.as_bytes(), Format::Classic).unwrap())
+}
+
+#[test]
+fn single_spaces() {
+    assert_eq!(classic("3\n2 3 3\n"), [[2], [3], [3]]);
+}
+
+#[test]
+fn double_spaces() {
+    assert_eq!(classic("3\n2  3   3\n"), [[2], [3], [3]]);
+}
+
+#[test]
+fn tabs() {
+    assert_eq!(classic("3\n2\t3 \t3\n"), [[2], [3], [3]]);
+}
+
+#[test]
+fn leading_and_trailing_spaces() {
+    assert_eq!(classic("  3 \n 2 3 3  \n"), [[2], [3], [3]]);
+}
+
+#[test]
+fn crlf_line_endings() {
+    assert_eq!(classic("3\r\n2 3 3\r\n"), [[2], [3], [3]]);
+}
+
+#[test]
+fn missing_final_newline() {
+    assert_eq!(classic("3\n2 3 3"), [[2], [3], [3]]);
+}
+
+#[test]
+fn wrapped_shortcuts() {
+    assert_eq!(classic("3\n2\n3\n\n3\n"), [[2], [3], [3]]);
+}
+
+#[test]
+fn everything_on_one_line() {
+    assert_eq!(classic("3 2 3 3"), [[2], [3], [3]]);
+}
+
+#[test]
+fn blank_lines() {
+    assert_eq!(classic("\n\n3\n\n2 3 3\n\n"), [[2], [3], [3]]);
+}
+
+#[test]
+fn tokens_across_buffer_boundaries() {
+    // A buffer this small splits every token, and error positions must not depend on it.
+    let tiny = |input: &'static str, capacity| BufReader::with_capacity(capacity, input.as_bytes());
+
+    let input = tiny("12\n10 11 12 12 12 12 12 12 12 12 12 12\n", 3);
+    let instance = parse(input, Format::Classic).unwrap();
+    assert_eq!(targets(&instance)[..2], [[10], [11]]);
+
+    let err = parse(tiny("3\n> =\n2 3 31\n", 2), Format::Classic).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::ShortcutOutOfRange {
+            line: 3,
+            column: 5,
+            value: 31,
+            n: 3
+        }
+    ));
+
+    let err = parse(tiny("3 1\n 12 3 45 6\n", 2), Format::Edges).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::MalformedLine {
+            line: 2,
+            found: 4,
+            ..
+        }
+    ));
+
+    let err = parse(tiny("3\n2 3 3\n  12\n", 1), Format::Classic).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::TrailingInput { line: 3, column: 3 }
+    ));
+}
+
+#[test]
+fn wrapped_weighted() {
+    let instance = parse("3\n5\n7 1 2\n3\n10 20\n30\n".as_bytes(), Format::Weighted).unwrap();
+    assert_eq!(instance.walking, [5, 7]);
+    assert_eq!(targets(&instance), [[1], [2], [3]]);
+    let costs = instance
+        .shortcuts
+        .iter()
+        .map(|s| s[0].cost)
+        .collect::<Vec<_>>();
+    assert_eq!(costs, [10, 20, 30]);
+}
+
+#[test]
+fn edges_with_irregular_whitespace() {
+    let instance = parse("3  2\n 1\t3  7 \r\n2 1\n".as_bytes(), Format::Edges).unwrap();
+    assert_eq!(instance.shortcuts[0], [Shortcut { to: 2, cost: 7 }]);
+    assert_eq!(instance.shortcuts[1], [Shortcut { to: 0, cost: 1 }]);
+}
+
+#[test]
+fn edge_cost_must_be_on_the_same_line() {
+    // Without the line break, the 2 would be the cost of the first edge.
+    let instance = parse("3 2\n1 3\n2 1\n".as_bytes(), Format::Edges).unwrap();
+    assert_eq!(instance.shortcuts[0], [Shortcut { to: 2, cost: 1 }]);
+    assert_eq!(instance.shortcuts[1], [Shortcut { to: 0, cost: 1 }]);
+}
+
+#[test]
+fn wrapped_queries() {
+    let mut reader = Reader::new("3\n2 3 3\n2\n1\n3 2  1\n".as_bytes());
+    reader.instance(Format::Classic).unwrap();
+    assert_eq!(reader.queries(3).unwrap(), [(0, 2), (1, 0)]);
+}
+
+#[test]
+fn huge_query_count() {
+    let mut reader = Reader::new("3\n2 3 3\n99999999999999999\n1 2\n".as_bytes());
+    reader.instance(Format::Classic).unwrap();
+    let err = reader.queries(3).unwrap_err();
+    assert!(matches!(err, ParseError::UnexpectedEnd { line: 5 }));
+}
+
+#[test]
+fn wrapped_cases() {
+    let mut reader = Reader::new("2 1 1 2\n2 1\n".as_bytes());
+    assert_eq!(reader.case_count().unwrap(), 2);
+    assert_eq!(targets(&reader.instance(Format::Classic).unwrap()), [[1]]);
+    assert_eq!(
+        targets(&reader.instance(Format::Classic).unwrap()),
+        [[2], [1]]
+    );
+}
+
+#[test]
+fn too_few_shortcuts() {
+    let err = parse("3\n2 3\n".as_bytes(), Format::Classic).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::ShortcutCount {
+            line: 2,
+            expected: 3,
+            found: 2
+        }
+    ));
+}
+
+#[test]
+fn too_many_shortcuts() {
+    let err = parse("3\n1 2 3 1 1 1 1\n".as_bytes(), Format::Classic).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::TrailingInput { line: 2, column: 7 }
+    ));
+
+    let err = parse("3\n1 2 3\ngarbage".as_bytes(), Format::Classic).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::TrailingInput { line: 3, column: 1 }
+    ));
+}
+
+#[test]
+fn huge_node_count() {
+    let err = parse("99999999999999999\n1\n".as_bytes(), Format::Classic).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::ShortcutCount {
+            line: 2,
+            expected: 99999999999999999,
+            found: 1
+        }
+    ));
+
+    let err = parse("99999999999999999\n1\n".as_bytes(), Format::Weighted).unwrap_err();
+    assert!(matches!(err, ParseError::CostCount { found: 1, .. }));
+
+    let err = parse("18446744073709551615 0\n".as_bytes(), Format::Edges).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::TooManyNodes {
+            line: 1,
+            n: 18446744073709551615
+        }
+    ));
+}
+
+#[test]
+fn invalid_token_position() {
+    let err = parse("3\n2  x 3\n".as_bytes(), Format::Classic).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::InvalidNumber { line: 2, column: 4, ref token } if token == "x"
+    ));
+}
+
+#[test]
+fn shortcut_out_of_range_on_wrapped_line() {
+    let err = parse("3\n2 3\n4\n".as_bytes(), Format::Classic).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::ShortcutOutOfRange {
+            line: 3,
+            column: 1,
+            value: 4,
+            n: 3
+        }
+    ));
+}
+
+#[test]
+fn too_many_values_on_edge_line() {
+    let err = parse("3 1\n1 2 3 4\n".as_bytes(), Format::Edges).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::MalformedLine {
+            line: 2,
+            found: 4,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn huge_edge_count() {
+    let err = parse("2 99999999999999999".as_bytes(), Format::Edges).unwrap_err();
+    assert!(matches!(err, ParseError::UnexpectedEnd { line: 2 }));
+}
+
+#[test]
+fn empty_input() {
+    let err = parse("".as_bytes(), Format::Classic).unwrap_err();
+    assert!(matches!(err, ParseError::UnexpectedEnd { line: 1 }));
+}
+
+#[test]
+fn directions() {
+    let instance = parse("5\n= > < x\n1 2 3 4 5\n".as_bytes(), Format::Classic).unwrap();
+    assert_eq!(
+        instance.directions,
+        [
+            Direction::Both,
+            Direction::Forward,
+            Direction::Backward,
+            Direction::Closed
+        ]
+    );
+}
+
+#[test]
+fn directions_are_optional() {
+    let instance = parse("3\n1 2 3\n".as_bytes(), Format::Classic).unwrap();
+    assert_eq!(instance.directions, [Direction::Both; 2]);
+}
+
+#[test]
+fn directions_before_edges() {
+    let instance = parse("3 1\n> x\n1 3 2\n".as_bytes(), Format::Edges).unwrap();
+    assert_eq!(instance.directions, [Direction::Forward, Direction::Closed]);
+    assert_eq!(instance.shortcuts[0], [Shortcut { to: 2, cost: 2 }]);
+}
+
+#[test]
+fn too_few_directions() {
+    let err = parse("3\n>\n1 2 3\n".as_bytes(), Format::Classic).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::DirectionCount {
+            line: 2,
+            expected: 2,
+            found: 1
+        }
+    ));
+}
+
+#[test]
+fn invalid_direction() {
+    let err = parse("3\n> ?\n1 2 3\n".as_bytes(), Format::Classic).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::InvalidDirection { line: 2, column: 3, ref token } if token == "?"
+    ));
+}
+
+#[test]
+fn invalid_shortcut_is_not_a_direction() {
+    let err = parse("3\n-1 2 3\n".as_bytes(), Format::Classic).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::InvalidNumber { line: 2, column: 1, ref token } if token == "-1"
+    ));
+}
+
+#[test]
+fn candidate_distances() {
+    let mut reader = Reader::new("0 -1 2\n3\n  \n".as_bytes());
+    assert_eq!(reader.distances(2).unwrap(), [Some(0), None]);
+    assert_eq!(reader.distances(2).unwrap(), [Some(2), Some(3)]);
+    reader.end().unwrap();
+}
+
+#[test]
+fn too_few_distances() {
+    let err = Reader::new("\n0 1\n".as_bytes()).distances(3).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::DistanceCount {
+            line: 2,
+            expected: 3,
+            found: 2
+        }
+    ));
+
+    let err = Reader::new("0 -2".as_bytes()).distances(2).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::InvalidNumber { line: 1, column: 3, ref token } if token == "-2"
+    ));
+}
+
+#[test]
+fn trailing_distances() {
+    let mut reader = Reader::new("0 1\n2\n".as_bytes());
+    reader.distances(2).unwrap();
+    assert!(matches!(
+        reader.end().unwrap_err(),
+        ParseError::TrailingInput { line: 2, column: 1 }
+    ));
+}
+
+#[test]
+fn instance_line() {
+    let instance = Reader::new("3\n4 5 6\n> x <\n1 2 3\n1 1 1\n".as_bytes())
+        .rings(true)
+        .instance(Format::Weighted)
+        .unwrap();
+    let line = instance.line();
+    assert!(line.is_ring());
+    assert_eq!(line.walking_costs(), [4, 5, 6]);
+    assert_eq!(
+        line.directions(),
+        [Direction::Forward, Direction::Closed, Direction::Backward]
+    );
+}
@@ -0,0 +1,48 @@
+use symmetrical_palm_tree::output::{Format, Writer};
+use symmetrical_palm_tree::{Direction, Line, LinearDijkstra};
+
+// Write the distances from node 1 of a line of 4 nodes whose first segment is closed, so that node
+// 0 cannot be reached.
+fn write(mut writer: Writer<Vec<u8>>) -> String {
+    let line =
+        Line::new(4).with_directions(vec![Direction::Closed, Direction::Both, Direction::Both]);
+    let mut solver = LinearDijkstra::with_topology(line, &[(1, 0)]);
+    while solver.pop().is_some() {}
+
+    writer.write(&solver).unwrap();
+    String::from_utf8(writer.into_inner().unwrap()).unwrap()
+}
+
+#[test]
+fn unreachable_nodes_in_each_format() {
+    assert_eq!(write(Writer::new(Vec::new(), Format::Plain)), "-1 0 1 2\n");
+    assert_eq!(
+        write(Writer::new(Vec::new(), Format::Json)),
+        "[{\"node\":1},{\"node\":2,\"distance\":0},{\"node\":3,\"distance\":1},\
+         {\"node\":4,\"distance\":2}]\n"
+    );
+    assert_eq!(
+        write(Writer::new(Vec::new(), Format::Csv)),
+        "node,distance\n1,-1\n2,0\n3,1\n4,2\n"
+    );
+}
+
+#[test]
+fn unreachable_marker() {
+    let writer = Writer::new(Vec::new(), Format::Plain).unreachable("inf");
+    assert_eq!(write(writer), "inf 0 1 2\n");
+}
+
+#[test]
+fn reachable_only() {
+    let writer = Writer::new(Vec::new(), Format::Plain).reachable_only(true);
+    assert_eq!(write(writer), "2:0 3:1 4:2\n");
+
+    let writer = Writer::new(Vec::new(), Format::Csv)
+        .reachable_only(true)
+        .predecessors(true);
+    assert_eq!(
+        write(writer),
+        "node,distance,predecessor\n2,0,\n3,1,2\n4,2,3\n"
+    );
+}
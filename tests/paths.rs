@@ -0,0 +1,172 @@
+mod common;
+
+use symmetrical_palm_tree::input::Instance;
+use symmetrical_palm_tree::queue::{BucketQueue, RadixHeap};
+use symmetrical_palm_tree::random::Rng;
+use symmetrical_palm_tree::{
+    reference, Direction, Engine, Line, LinearBfs, LinearDijkstra, Step, PATH_COUNT_MODULUS,
+};
+
+const INSTANCES: u64 = 2000;
+
+// Run a full search, taking the `(from, to, cost)` shortcuts of each examined node.
+fn search<E: Engine<Cost = usize>>(solver: &mut E, shortcuts: &[(usize, usize, usize)]) {
+    common::search(solver, |position| {
+        let leaving = shortcuts
+            .iter()
+            .filter(move |&&(from, _, _)| from == position);
+        leaving.map(|&(_, to, cost)| (to, cost))
+    });
+}
+
+#[test]
+fn hand_checked_counts() {
+    // 0 - 1 - 2 - 3, with shortcuts from 0 to 1 and from 0 to 2 that cost as much as walking.
+    // Node 1 has two routes (walking or the shortcut), node 2 has those two followed by a walk and
+    // the direct shortcut, and node 3 has the three routes to node 2 followed by a walk.
+    let mut solver = LinearDijkstra::new(4);
+    search(&mut solver, &[(0, 1, 1), (0, 2, 2)]);
+    assert_eq!(solver.distances(), [Some(0), Some(1), Some(2), Some(3)]);
+    assert_eq!(solver.path_counts(), [1, 2, 3, 3]);
+}
+
+#[test]
+fn longer_routes_do_not_count() {
+    // The shortcut from 0 to 2 costs more than walking, and the one from 1 to 3 less.
+    let mut solver = LinearDijkstra::new(4);
+    search(&mut solver, &[(0, 2, 3), (1, 3, 1)]);
+    assert_eq!(solver.distances(), [Some(0), Some(1), Some(2), Some(2)]);
+    assert_eq!(solver.path_counts(), [1, 1, 1, 1]);
+}
+
+#[test]
+fn counts_wrap_around_the_modulus() {
+    // With a shortcut alongside each segment, node k has 2^k routes.
+    let shortcuts = (0..70).map(|i| (i, i + 1, 1)).collect::<Vec<_>>();
+
+    let mut solver = LinearDijkstra::new(71);
+    solver.set_path_count_modulus(5);
+    solver.reset(&[(0, 0)]);
+    search(&mut solver, &shortcuts);
+    assert_eq!(solver.path_counts()[..8], [1, 2, 4, 3, 1, 2, 4, 3]);
+
+    // 2^64 is 1 modulo 2^64 - 1, which only works out if adding counts does not overflow.
+    let mut solver = LinearBfs::new(71);
+    solver.set_path_count_modulus(usize::MAX);
+    solver.reset(&[(0, 0)]);
+    search(&mut solver, &shortcuts);
+    assert_eq!(solver.path_counts()[63], 1 << 63);
+    assert_eq!(solver.path_counts()[64], 1);
+    assert_eq!(solver.path_counts()[65], 2);
+
+    // Everything is 0 modulo 1, even the empty route to the source.
+    let mut solver = LinearDijkstra::new(3);
+    solver.set_path_count_modulus(1);
+    solver.reset(&[(0, 0)]);
+    search(&mut solver, &shortcuts[..2]);
+    assert_eq!(solver.path_counts(), [0, 0, 0]);
+}
+
+#[test]
+fn modulus_changes_at_the_next_reset() {
+    let shortcuts = (0..7).map(|i| (i, i + 1, 1)).collect::<Vec<_>>();
+
+    // Changing the modulus halfway through the search leaves it alone.
+    let mut solver = LinearDijkstra::new(8);
+    let state = solver.pop().unwrap();
+    solver.push(state.cost + 1, Step::shortcut(0, 1));
+    solver.set_path_count_modulus(3);
+    search(&mut solver, &shortcuts[1..]);
+    assert_eq!(solver.path_counts()[7], 128);
+
+    solver.reset(&[(0, 0)]);
+    search(&mut solver, &shortcuts);
+    assert_eq!(solver.path_counts()[7], 128 % 3);
+}
+
+// Count the shortest routes from `sources` to each node by brute force: the routes to a node are
+// those of each source at that distance, and those reaching the origin of each edge that leads to
+// it along a shortest path. Since all costs are positive, the origins of these edges are closer.
+fn brute_force(instance: &Instance, sources: &[(usize, usize)], modulus: usize) -> Vec<usize> {
+    let distances = reference::distances(instance, sources);
+    let edges = reference::edges(instance);
+
+    let mut order = (0..instance.len())
+        .filter(|&node| distances[node].is_some())
+        .collect::<Vec<_>>();
+    order.sort_by_key(|&node| distances[node]);
+
+    let mut counts = vec![0; instance.len()];
+    for node in order {
+        let distance = distances[node];
+        let source = sources
+            .iter()
+            .any(|&(source, cost)| source == node && Some(cost) == distance);
+        let mut count = usize::from(source) % modulus;
+        for &(from, to, cost) in &edges {
+            if to == node && distances[from].map(|d| d + cost) == distance {
+                count = (count + counts[from]) % modulus;
+            }
+        }
+        counts[node] = count;
+    }
+
+    counts
+}
+
+// The route counts of a full search on `instance` with `engine`.
+fn counts<E: Engine<Cost = usize, Topology = Line>>(
+    engine: &mut E,
+    instance: &Instance,
+    sources: &[(usize, usize)],
+) -> Vec<usize> {
+    common::solve(engine, instance, sources);
+    engine.path_counts().to_vec()
+}
+
+#[test]
+fn engines_match_brute_force() {
+    let mut binary = LinearDijkstra::new(1);
+    let mut dial = LinearDijkstra::with_queue(BucketQueue::new(), Vec::new(), &[]);
+    let mut radix = LinearDijkstra::with_queue(RadixHeap::new(), Vec::new(), &[]);
+    for seed in 0..INSTANCES {
+        let mut rng = Rng::new(seed);
+        // Costs between 1 and 3 make many ties, but no step of cost 0, along which routes are not
+        // counted.
+        let directions = [Direction::Both, Direction::Forward, Direction::Backward];
+        let instance = common::instance(&mut rng, 12, 1..=3, &directions);
+        let sources = common::sources(&mut rng, instance.len());
+
+        // Small moduli make the counts wrap around.
+        let modulus = if rng.chance(1, 2) {
+            PATH_COUNT_MODULUS
+        } else {
+            rng.between(2, 7)
+        };
+        binary.set_path_count_modulus(modulus);
+        dial.set_path_count_modulus(modulus);
+        radix.set_path_count_modulus(modulus);
+
+        let expected = brute_force(&instance, &sources, modulus);
+        assert_eq!(
+            counts(&mut binary, &instance, &sources),
+            expected,
+            "seed {}: {:?} from {:?}",
+            seed,
+            instance,
+            sources
+        );
+        assert_eq!(
+            counts(&mut dial, &instance, &sources),
+            expected,
+            "seed {}",
+            seed
+        );
+        assert_eq!(
+            counts(&mut radix, &instance, &sources),
+            expected,
+            "seed {}",
+            seed
+        );
+    }
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use symmetrical_palm_tree::input::Instance;
+use symmetrical_palm_tree::random::Rng;
+use symmetrical_palm_tree::{reference, LinearDijkstra};
+
+const CASES: u64 = 2000;
+
+// A random shortcut array for a classic instance with up to 200 nodes. Half of the arrays only
+// have shortcuts to nearby nodes, which makes walking and shortcuts compete more often.
+fn shortcut_array(rng: &mut Rng) -> Vec<usize> {
+    let n = rng.between(1, 200);
+    let local = rng.chance(1, 2);
+    (0..n)
+        .map(|i| {
+            if local {
+                rng.between(i.saturating_sub(5), (i + 5).min(n - 1))
+            } else {
+                rng.below(n)
+            }
+        })
+        .collect()
+}
+
+// The distances from node 0 of the classic instance with the given shortcuts.
+fn classic(shortcuts: &[usize]) -> Vec<usize> {
+    let mut solver = LinearDijkstra::new(shortcuts.len());
+    common::search(&mut solver, |position| Some((shortcuts[position], 1)));
+
+    // Every node of a classic instance can be walked to.
+    solver.distances().iter().map(|d| d.unwrap()).collect()
+}
+
+// Check `property` on the distances of many random classic instances, reporting the seed of the
+// first failure.
+fn check_classic(property: impl Fn(&[usize], &[usize]) -> bool) {
+    for seed in 0..CASES {
+        let shortcuts = shortcut_array(&mut Rng::new(seed));
+        let distances = classic(&shortcuts);
+        assert!(
+            property(&shortcuts, &distances),
+            "seed {}: shortcuts {:?} give {:?}",
+            seed,
+            shortcuts,
+            distances
+        );
+    }
+}
+
+#[test]
+fn start_is_at_distance_zero() {
+    check_classic(|_, d| d[0] == 0);
+}
+
+#[test]
+fn neighbours_differ_by_at_most_one() {
+    check_classic(|_, d| d.windows(2).all(|pair| pair[0].abs_diff(pair[1]) <= 1));
+}
+
+#[test]
+fn shortcuts_cost_at_most_one() {
+    check_classic(|a, d| (0..a.len()).all(|i| d[a[i]] <= d[i] + 1));
+}
+
+#[test]
+fn walking_is_an_upper_bound() {
+    check_classic(|_, d| d.iter().enumerate().all(|(i, &d)| d <= i));
+}
+
+// A random weighted instance with up to 50 nodes, with segments that can be one-way or closed.
+fn weighted(rng: &mut Rng) -> Instance {
+    common::instance(rng, 50, 0..=10, &common::ALL_DIRECTIONS)
+}
+
+// A solver that has run a full search on `instance` from node 0.
+fn solve(instance: &Instance) -> LinearDijkstra {
+    let mut solver = LinearDijkstra::new(1);
+    common::solve(&mut solver, instance, &[(0, 0)]);
+    solver
+}
+
+#[test]
+fn triangle_inequality() {
+    for seed in 0..CASES {
+        let instance = weighted(&mut Rng::new(seed));
+        let d = solve(&instance).distances().to_vec();
+
+        // No edge leads to a node more cheaply than its distance, or to an unreachable node.
+        for (from, to, cost) in reference::edges(&instance) {
+            if let Some(distance) = d[from] {
+                assert!(
+                    d[to].is_some_and(|d| d <= distance + cost),
+                    "seed {}: edge {} -> {} with cost {} in {:?}",
+                    seed,
+                    from,
+                    to,
+                    cost,
+                    instance
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn distances_are_achieved_by_routes() {
+    for seed in 0..CASES {
+        let instance = weighted(&mut Rng::new(seed));
+        let solver = solve(&instance);
+        let edges = reference::edges(&instance);
+
+        // The route to each reachable node costs exactly its distance, taking the cheapest edge
+        // between each pair of consecutive nodes.
+        for (node, &distance) in solver.distances().iter().enumerate() {
+            let Some(distance) = distance else {
+                assert!(solver.path_to(node).is_none(), "seed {}", seed);
+                continue;
+            };
+
+            let route = solver.path_to(node).unwrap();
+            let cost = route
+                .iter()
+                .map(|step| {
+                    edges
+                        .iter()
+                        .filter(|&&(from, to, _)| (from, to) == (step.from, step.to))
+                        .map(|&(_, _, cost)| cost)
+                        .min()
+                        .unwrap()
+                })
+                .sum::<usize>();
+            assert_eq!(cost, distance, "seed {}: route to {}", seed, node);
+        }
+    }
+}
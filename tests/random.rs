@@ -0,0 +1,30 @@
+use symmetrical_palm_tree::random::Rng;
+
+#[test]
+fn same_seed_same_numbers() {
+    let numbers = |seed| {
+        let mut rng = Rng::new(seed);
+        (0..100).map(|_| rng.next_u64()).collect::<Vec<_>>()
+    };
+    assert_eq!(numbers(0), numbers(0));
+    assert_ne!(numbers(0), numbers(1));
+}
+
+#[test]
+fn ranges_are_respected() {
+    let mut rng = Rng::new(42);
+    let mut seen = [false; 4];
+    for _ in 0..1000 {
+        let n = rng.between(3, 6);
+        assert!((3..=6).contains(&n));
+        seen[n - 3] = true;
+
+        assert!(rng.below(7) < 7);
+        assert!(!rng.chance(0, 3));
+        assert!(rng.chance(3, 3));
+    }
+    assert_eq!(seen, [true; 4]);
+
+    assert_eq!(rng.between(5, 5), 5);
+    rng.between(0, usize::MAX);
+}
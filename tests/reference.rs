@@ -0,0 +1,102 @@
+mod common;
+
+use symmetrical_palm_tree::input::{Instance, Shortcut, Shortcuts};
+use symmetrical_palm_tree::queue::{BucketQueue, RadixHeap};
+use symmetrical_palm_tree::random::Rng;
+use symmetrical_palm_tree::{reference, Direction, Engine, LinearBfs, LinearDijkstra};
+
+const INSTANCES: u64 = 5000;
+
+// A random instance with up to 12 nodes. Small costs make ties, and therefore mistakes in their
+// handling, more likely. Half of the instances have unit costs, and half two-way segments only.
+fn instance(rng: &mut Rng) -> Instance {
+    let costs = if rng.chance(1, 2) { 0..=5 } else { 1..=1 };
+    let directions: &[Direction] = if rng.chance(1, 2) {
+        &[Direction::Both]
+    } else {
+        &common::ALL_DIRECTIONS
+    };
+    common::instance(rng, 12, costs, directions)
+}
+
+#[test]
+fn reference_on_a_small_instance() {
+    // 0 - 1 - 2 - 3 with a one-way second segment, and a costly shortcut from 3 back to 0.
+    let instance = Instance {
+        walking: vec![1, 2, 1],
+        directions: vec![Direction::Both, Direction::Forward, Direction::Both],
+        ring: false,
+        shortcuts: Shortcuts::from_edges(4, &[(3, Shortcut { to: 0, cost: 5 })]),
+    };
+    assert_eq!(
+        reference::distances(&instance, &[(0, 0)]),
+        [Some(0), Some(1), Some(3), Some(4)]
+    );
+    assert_eq!(
+        reference::distances(&instance, &[(2, 0)]),
+        [Some(6), Some(7), Some(0), Some(1)]
+    );
+    assert_eq!(
+        reference::distances(&instance, &[(3, 9), (1, 0)]),
+        [Some(1), Some(0), Some(2), Some(3)]
+    );
+}
+
+#[test]
+fn dijkstra_matches_reference() {
+    let mut solver = LinearDijkstra::new(1);
+    for seed in 0..INSTANCES {
+        let mut rng = Rng::new(seed);
+        let instance = instance(&mut rng);
+        let sources = common::sources(&mut rng, instance.len());
+
+        common::solve(&mut solver, &instance, &sources);
+        assert_eq!(
+            solver.distances(),
+            reference::distances(&instance, &sources),
+            "seed {}: {:?} from {:?}",
+            seed,
+            instance,
+            sources
+        );
+    }
+}
+
+#[test]
+fn integer_queues_match_reference() {
+    let mut dial = LinearDijkstra::with_queue(BucketQueue::new(), Vec::new(), &[]);
+    let mut radix = LinearDijkstra::with_queue(RadixHeap::new(), Vec::new(), &[]);
+    for seed in 0..INSTANCES {
+        let mut rng = Rng::new(seed);
+        let instance = instance(&mut rng);
+        let sources = common::sources(&mut rng, instance.len());
+
+        let expected = reference::distances(&instance, &sources);
+        common::solve(&mut dial, &instance, &sources);
+        assert_eq!(dial.distances(), expected, "seed {}", seed);
+        common::solve(&mut radix, &instance, &sources);
+        assert_eq!(radix.distances(), expected, "seed {}", seed);
+    }
+}
+
+#[test]
+fn bfs_matches_reference() {
+    let mut bfs = LinearBfs::new(1);
+    let mut checked = 0;
+    for seed in 0..INSTANCES {
+        let mut rng = Rng::new(seed);
+        let instance = instance(&mut rng);
+        let sources = common::sources(&mut rng, instance.len());
+        if instance.max_cost() > 1 {
+            continue;
+        }
+
+        let expected = reference::distances(&instance, &sources);
+        common::solve(&mut bfs, &instance, &sources);
+        assert_eq!(bfs.distances(), expected, "seed {}", seed);
+        checked += 1;
+    }
+
+    // Make sure that enough instances had small enough costs.
+    assert!(checked > INSTANCES / 4);
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use symmetrical_palm_tree::input::{Format, ParseError, Reader};
+use symmetrical_palm_tree::queue::BucketQueue;
+use symmetrical_palm_tree::{Direction, Engine, Line, LinearBfs, LinearDijkstra, StepKind};
+
+// Run a full search without any shortcut, and return the distances, which must all be reachable.
+fn walk<E: Engine<Cost = usize>>(mut solver: E) -> Vec<usize> {
+    while solver.pop().is_some() {}
+    solver.distances().iter().map(|d| d.unwrap()).collect()
+}
+
+#[test]
+fn walking_wraps_around() {
+    let solver = LinearDijkstra::with_topology(Line::ring(6), &[(0, 0)]);
+    assert_eq!(walk(solver), [0, 1, 2, 3, 2, 1]);
+}
+
+#[test]
+fn single_node_ring() {
+    let solver = LinearDijkstra::with_topology(Line::ring(1), &[(0, 0)]);
+    assert_eq!(walk(solver), [0]);
+}
+
+#[test]
+fn weighted_ring() {
+    // The segment between the last node and 0 is the cheapest way around.
+    let line = Line::ring_with_walking_costs(vec![5, 5, 5, 1]);
+    let solver = LinearDijkstra::with_topology(line, &[(0, 0)]);
+    assert_eq!(walk(solver), [0, 5, 6, 1]);
+}
+
+#[test]
+fn one_way_ring() {
+    let line = Line::ring(5).with_directions(vec![Direction::Forward; 5]);
+    let solver = LinearDijkstra::with_topology(line, &[(3, 0)]);
+    assert_eq!(walk(solver), [2, 3, 4, 0, 1]);
+}
+
+#[test]
+fn closed_segment_opens_the_ring() {
+    let mut directions = vec![Direction::Both; 5];
+    directions[4] = Direction::Closed;
+    let line = Line::ring(5).with_directions(directions);
+    let solver = LinearDijkstra::with_topology(line, &[(0, 0)]);
+    assert_eq!(walk(solver), [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn engines_agree() {
+    let shortcuts = [3, 7, 0, 9, 2, 2, 5, 1, 8, 4];
+
+    fn run<E: Engine<Cost = usize>>(mut solver: E, shortcuts: &[usize]) -> Vec<usize> {
+        common::search(&mut solver, |position| Some((shortcuts[position], 1)));
+        solver.distances().iter().map(|d| d.unwrap()).collect()
+    }
+
+    let line = Line::ring(shortcuts.len());
+    let dijkstra = run(
+        LinearDijkstra::with_topology(line.clone(), &[(6, 0)]),
+        &shortcuts,
+    );
+    let bfs = run(
+        LinearBfs::with_topology(line.clone(), &[(6, 0)]),
+        &shortcuts,
+    );
+    let mut dial = LinearDijkstra::with_queue(BucketQueue::new(), Vec::new(), &[]);
+    dial.reset_with_topology(line, &[(6, 0)]);
+    assert_eq!(dijkstra, [3, 2, 2, 3, 2, 1, 0, 1, 2, 3]);
+    assert_eq!(bfs, dijkstra);
+    assert_eq!(run(dial, &shortcuts), dijkstra);
+}
+
+#[test]
+fn path_across_the_end() {
+    let mut solver = LinearDijkstra::with_topology(Line::ring(5), &[(1, 0)]);
+    while solver.pop().is_some() {}
+
+    let path = solver.path_to(4).unwrap();
+    let steps = path
+        .iter()
+        .map(|step| (step.from, step.to, step.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        steps,
+        [(1, 0, StepKind::WalkLeft), (0, 4, StepKind::WalkLeft)]
+    );
+}
+
+#[test]
+fn both_ways_around_are_counted() {
+    let mut solver = LinearDijkstra::with_topology(Line::ring(4), &[(0, 0)]);
+    while solver.pop().is_some() {}
+    assert_eq!(solver.path_counts(), [1, 1, 2, 1]);
+}
+
+#[test]
+fn reset_keeps_the_ring() {
+    let mut solver = LinearDijkstra::with_topology(Line::ring(4), &[(0, 0)]);
+    while solver.pop().is_some() {}
+    solver.reset(&[(3, 0)]);
+    assert_eq!(walk(solver), [1, 2, 1, 0]);
+}
+
+#[test]
+fn line_constructors() {
+    let mut solver = LinearDijkstra::with_line(Line::ring(4), &[(0, 0)]);
+    while solver.pop().is_some() {}
+    solver.reset_with_line(Line::ring(5), &[(4, 0)]);
+    assert_eq!(walk(solver), [1, 2, 2, 1, 0]);
+
+    let mut engine = LinearBfs::with_line(Line::new(3), &[(0, 0)]);
+    while engine.pop().is_some() {}
+    engine.reset_with_line(Line::ring(3), &[(2, 0)]);
+    assert_eq!(walk(engine), [1, 1, 0]);
+}
+
+#[test]
+fn parse_weighted_ring() {
+    let mut reader = Reader::new("3\n1 2 3\n> = <\n1 2 3\n4 5 6\n".as_bytes()).rings(true);
+    let instance = reader.instance(Format::Weighted).unwrap();
+    assert!(instance.ring);
+    assert_eq!(instance.walking, [1, 2, 3]);
+    assert_eq!(
+        instance.directions,
+        [Direction::Forward, Direction::Both, Direction::Backward]
+    );
+}
+
+#[test]
+fn parse_edges_ring() {
+    let mut reader = Reader::new("3 1\n1 3\n".as_bytes()).rings(true);
+    let instance = reader.instance(Format::Edges).unwrap();
+    assert_eq!(instance.walking, [1, 1, 1]);
+    assert_eq!(instance.directions, [Direction::Both; 3]);
+}
+
+#[test]
+fn ring_needs_a_direction_per_node() {
+    let mut reader = Reader::new("3\n> >\n1 2 3\n".as_bytes()).rings(true);
+    let err = reader.instance(Format::Classic).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::DirectionCount {
+            line: 2,
+            expected: 3,
+            found: 2
+        }
+    ));
+}
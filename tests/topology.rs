@@ -0,0 +1,124 @@
+mod common;
+
+use symmetrical_palm_tree::adjacency::Adjacency;
+use symmetrical_palm_tree::grid::{Connectivity, Grid};
+use symmetrical_palm_tree::queue::RadixHeap;
+use symmetrical_palm_tree::{
+    Bfs, Dijkstra, Engine, Line, LinearDijkstra, Step, StepKind, Topology,
+};
+
+// Run a full search, pushing the shortcut of each node if it has one, and return the distances.
+fn search<E: Engine<Cost = usize>>(
+    solver: &mut E,
+    shortcuts: &[Option<usize>],
+) -> Vec<Option<usize>> {
+    common::search(solver, |position| shortcuts[position].map(|to| (to, 1)));
+    solver.distances().to_vec()
+}
+
+// A complete binary tree where node i has children 2i + 1 and 2i + 2, walked from parent to child
+// only.
+struct Tree(usize);
+
+impl Topology for Tree {
+    type Cost = usize;
+
+    fn len(&self) -> usize {
+        self.0
+    }
+
+    fn neighbours(&self, position: usize) -> impl Iterator<Item = (Step, usize)> {
+        (2 * position + 1..=2 * position + 2)
+            .filter(|&child| child < self.0)
+            .map(move |to| {
+                let step = Step {
+                    from: position,
+                    to,
+                    kind: StepKind::Walk,
+                };
+                (step, 1)
+            })
+    }
+}
+
+#[test]
+fn line_is_the_default() {
+    let mut solver: Dijkstra = Dijkstra::with_topology(Line::new(4), &[(1, 0)]);
+    assert_eq!(
+        search(&mut solver, &[None; 4]),
+        search(&mut LinearDijkstra::with_start(4, 1), &[None; 4])
+    );
+}
+
+#[test]
+fn adjacency_lists() {
+    let graph = Adjacency::from_edges(4, &[(0, 1, 4), (0, 2, 1), (2, 1, 2), (1, 3, 1)]);
+    assert_eq!(graph.edges(0), [(1, 4), (2, 1)]);
+    assert!(graph.edges(3).is_empty());
+
+    let mut solver = Dijkstra::with_topology(graph, &[(0, 0)]);
+    assert_eq!(
+        search(&mut solver, &[None; 4]),
+        [Some(0), Some(3), Some(1), Some(4)]
+    );
+}
+
+#[test]
+fn adjacency_lists_with_shortcuts() {
+    let graph = Adjacency::from_edges(4, &[(0, 1, 4), (1, 2, 4), (2, 3, 4)]);
+    let mut solver = Dijkstra::with_queue_and_topology(RadixHeap::new(), graph, &[(0, 0)]);
+    assert_eq!(
+        search(&mut solver, &[Some(2), None, None, None]),
+        [Some(0), Some(4), Some(1), Some(5)]
+    );
+}
+
+#[test]
+fn adjacency_lists_can_leave_nodes_unreachable() {
+    let graph = Adjacency::from_edges(3, &[(1, 0, 1)]);
+    let mut solver = Dijkstra::with_topology(graph, &[(0, 0)]);
+    assert_eq!(search(&mut solver, &[None; 3]), [Some(0), None, None]);
+}
+
+#[test]
+fn custom_topology() {
+    let mut solver = Dijkstra::with_topology(Tree(7), &[(0, 0)]);
+    assert_eq!(
+        search(&mut solver, &[None; 7]),
+        [
+            Some(0),
+            Some(1),
+            Some(1),
+            Some(2),
+            Some(2),
+            Some(2),
+            Some(2)
+        ]
+    );
+}
+
+#[test]
+fn bfs_agrees_with_dijkstra_on_grids() {
+    let grid = Grid::new(5, 4)
+        .with_connectivity(Connectivity::Eight)
+        .with_obstacles(&[(1, 1), (2, 1), (3, 1), (3, 2)]);
+    let mut shortcuts = vec![None; grid.len()];
+    shortcuts[grid.node(4, 0)] = Some(grid.node(0, 3));
+
+    let dijkstra = search(
+        &mut Dijkstra::with_topology(grid.clone(), &[(2, 0)]),
+        &shortcuts,
+    );
+    let bfs = search(&mut Bfs::with_topology(grid, &[(2, 0)]), &shortcuts);
+    assert_eq!(bfs, dijkstra);
+}
+
+#[test]
+fn engines_reset_with_another_topology() {
+    let mut solver = Bfs::with_topology(Adjacency::from_edges(2, &[(0, 1, 1)]), &[(0, 0)]);
+    assert_eq!(search(&mut solver, &[None; 2]), [Some(0), Some(1)]);
+
+    let graph = Adjacency::from_edges(3, &[(2, 1, 0), (1, 0, 1)]);
+    solver.reset_with_topology(graph, &[(2, 0)]);
+    assert_eq!(search(&mut solver, &[None; 3]), [Some(1), Some(0), Some(0)]);
+}